@@ -0,0 +1,63 @@
+//! Measures how quickly `World::tick_into` simulates big random soups, under each edge behavior. Run it with `cargo
+//! bench`.
+//!
+//! This is a small hand-rolled harness rather than criterion, so that benchmarking doesn't need any extra dependencies.
+//! Each case is warmed up first, and then timed over enough generations to take about a second.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use conway::{
+    random::Rng,
+    world::{EdgeBehavior, World},
+};
+
+/// Roughly how long each case is timed for.
+const TARGET: Duration = Duration::from_secs(1);
+
+fn main() {
+    for (width, height) in [(64, 64), (256, 256), (1024, 1024)] {
+        for edges in [EdgeBehavior::Dead, EdgeBehavior::Wrap] {
+            let mut world = World::random(width, height, 0.35, &mut Rng::new(1));
+            world.set_edge_behavior(edges);
+            bench(
+                &format!("{}x{}, {} edges", width, height, edges.name()),
+                world,
+            );
+        }
+    }
+}
+
+/// Ticks `world` over and over, and prints how long each generation took on average.
+fn bench(name: &str, mut world: World) {
+    let mut next = World::new(world.width(), world.height());
+
+    // The first few generations are used to work out how many generations fit in `TARGET`.
+    let start = Instant::now();
+    let mut warmup = 0;
+
+    while start.elapsed() < TARGET / 10 {
+        world.tick_into(&mut next);
+        std::mem::swap(&mut world, &mut next);
+        warmup += 1;
+    }
+
+    let generations = (warmup * 10).max(1);
+    let start = Instant::now();
+
+    for _ in 0..generations {
+        black_box(&world).tick_into(&mut next);
+        std::mem::swap(&mut world, &mut next);
+    }
+
+    let per_generation = start.elapsed() / generations;
+    let cells = (world.width() * world.height()) as f64;
+    let per_cell = per_generation.as_secs_f64() * 1e9 / cells;
+
+    println!(
+        "{:<28} {:>10.3?} per generation, {:>6.2} ns per cell ({} generations)",
+        name, per_generation, per_cell, generations
+    );
+}
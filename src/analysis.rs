@@ -0,0 +1,258 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use crate::world::World;
+
+/// A pattern that has been seen to repeat itself, possibly in a different place.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cycle {
+    /// How many generations it takes for the pattern to repeat.
+    pub period: usize,
+    /// How far the pattern moves over one period. This is `(0, 0)` for still lifes and oscillators.
+    pub displacement: (isize, isize),
+}
+
+impl Cycle {
+    pub fn is_still_life(&self) -> bool {
+        self.period == 1 && !self.is_spaceship()
+    }
+
+    pub fn is_spaceship(&self) -> bool {
+        self.displacement != (0, 0)
+    }
+
+    /// Describes the speed of a spaceship in the usual `c/n` notation, along with its direction, e.g. `c/4 diagonal`.
+    pub fn velocity(&self) -> String {
+        let (dx, dy) = (
+            self.displacement.0.unsigned_abs(),
+            self.displacement.1.unsigned_abs(),
+        );
+        let distance = dx.max(dy);
+
+        if distance == 0 {
+            return "0".to_string();
+        }
+
+        // Speeds are written in lowest terms, e.g. the lightweight spaceship moves 2 cells every 4 generations, which is
+        // c/2 rather than 2c/4.
+        let divisor = gcd(distance, self.period);
+        let (distance, period) = (distance / divisor, self.period / divisor);
+
+        let speed = match distance {
+            1 => format!("c/{}", period),
+            _ => format!("{}c/{}", distance, period),
+        };
+
+        let direction = if dx == 0 || dy == 0 {
+            "orthogonal"
+        } else if dx == dy {
+            "diagonal"
+        } else {
+            "oblique"
+        };
+
+        format!("{} {}", speed, direction)
+    }
+}
+
+impl Display for Cycle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_still_life() {
+            write!(f, "still life")
+        } else if self.is_spaceship() {
+            write!(f, "period {} spaceship, {}", self.period, self.velocity())
+        } else {
+            write!(f, "period {} oscillator, velocity 0", self.period)
+        }
+    }
+}
+
+/// Keeps a bounded buffer of recent generations, and checks each new generation against them to find cycles.
+#[derive(Debug, Clone)]
+pub struct CycleDetector {
+    history: VecDeque<Snapshot>,
+    capacity: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Snapshot {
+    pattern: World,
+    // The top-left corner of the pattern's bounding box, used to work out how far it has moved.
+    origin: (usize, usize),
+}
+
+impl CycleDetector {
+    /// How many generations are remembered by default. Cycles with a longer period than this won't be detected.
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    pub fn new(capacity: usize) -> Self {
+        CycleDetector {
+            history: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Records the next generation, returning the cycle it completes, if any. The most recent matching generation is
+    /// used, so the period is always the shortest one.
+    pub fn observe(&mut self, world: &World) -> Option<Cycle> {
+        let snapshot = Snapshot {
+            pattern: world.trimmed(),
+            origin: world
+                .bounding_box()
+                .map_or((0, 0), |(top_left, _)| top_left),
+        };
+
+        let cycle = self
+            .history
+            .iter()
+            .rev()
+            .enumerate()
+            .find(|(_, previous)| same_cells(&previous.pattern, &snapshot.pattern))
+            .map(|(index, previous)| Cycle {
+                period: index + 1,
+                displacement: (
+                    snapshot.origin.0 as isize - previous.origin.0 as isize,
+                    snapshot.origin.1 as isize - previous.origin.1 as isize,
+                ),
+            });
+
+        if self.history.len() == self.capacity {
+            self.history.pop_front();
+        }
+
+        if self.capacity > 0 {
+            self.history.push_back(snapshot);
+        }
+
+        cycle
+    }
+
+    /// Forgets every generation seen so far.
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+}
+
+/// Checks whether two worlds have exactly the same cells. Snapshots are already trimmed down to their bounding boxes,
+/// so unlike `World::same_pattern`, there's no need to trim them again.
+fn same_cells(left: &World, right: &World) -> bool {
+    left.width() == right.width()
+        && left.height() == right.height()
+        && (0..left.height()).all(|y| left.row(y) == right.row(y))
+}
+
+/// The greatest common divisor of two numbers, which is `b` if `a` is 0.
+fn gcd(mut a: usize, mut b: usize) -> usize {
+    while a != 0 {
+        (a, b) = (b % a, a);
+    }
+
+    b
+}
+
+impl Default for CycleDetector {
+    fn default() -> Self {
+        CycleDetector::new(CycleDetector::DEFAULT_CAPACITY)
+    }
+}
+
+impl World {
+    /// Ticks the world under its own rule until it settles into a still life or an oscillator, or until `max_gens`
+    /// generations have passed. This returns the last world computed, along with the generation where it first entered its final
+    /// cycle. The generation is `None` if the world didn't settle in time. Spaceships don't count as settling, since
+    /// they never stop changing.
+    pub fn run_until_stable(self, max_gens: usize) -> (World, Option<usize>) {
+        let mut detector = CycleDetector::default();
+        let rule = self.rule();
+        let mut next = World::new(self.width(), self.height());
+        let mut world = self;
+
+        detector.observe(&world);
+
+        for generation in 1..=max_gens {
+            world.tick_into_with(&rule, &mut next);
+            std::mem::swap(&mut world, &mut next);
+
+            match detector.observe(&world) {
+                Some(cycle) if !cycle.is_spaceship() => {
+                    return (world, Some(generation - cycle.period))
+                }
+                _ => {}
+            }
+        }
+
+        (world, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    #[test]
+    fn speeds_are_in_lowest_terms() {
+        let lwss = Cycle {
+            period: 4,
+            displacement: (-2, 0),
+        };
+        let glider = Cycle {
+            period: 4,
+            displacement: (1, 1),
+        };
+
+        assert_eq!(lwss.velocity(), "c/2 orthogonal");
+        assert_eq!(glider.velocity(), "c/4 diagonal");
+    }
+
+    #[test]
+    fn the_lightweight_spaceship_moves_at_half_the_speed_of_light() {
+        let mut world = Format::Rle
+            .parse("x = 30, y = 10\n3$12bo2bo$11bo$11bo3bo$11b4o!")
+            .unwrap();
+        let mut detector = CycleDetector::default();
+        let mut cycle = detector.observe(&world);
+
+        while cycle.is_none() {
+            world = world.tick();
+            cycle = detector.observe(&world);
+        }
+
+        let cycle = cycle.unwrap();
+        assert_eq!(cycle.period, 4);
+        assert_eq!(cycle.velocity(), "c/2 orthogonal");
+    }
+
+    #[test]
+    fn still_lifes_settle_with_a_period_of_1() {
+        let world = |rows| Format::Plaintext.parse(rows).unwrap();
+
+        // This becomes a block straight away, which is seen to be still once it's been a block for a generation.
+        let (settled, generation) = world("OO.\nO..\n...").run_until_stable(10);
+        assert_eq!(settled, world("OO.\nOO.\n..."));
+        assert_eq!(generation, Some(1));
+    }
+
+    #[test]
+    fn blinkers_settle_with_a_period_of_2() {
+        let blinker = Format::Plaintext.parse("...\nOOO\n...").unwrap();
+
+        // The blinker is detected once it's gone all the way around its cycle, so it's back where it started.
+        let (settled, generation) = blinker.clone().run_until_stable(10);
+        assert_eq!(settled, blinker);
+        assert_eq!(generation, Some(0));
+
+        // Stopping halfway through the cycle isn't enough to notice it.
+        let (unsettled, generation) = blinker.clone().run_until_stable(1);
+        assert_eq!(unsettled, blinker.tick());
+        assert_eq!(generation, None);
+    }
+
+    #[test]
+    fn spaceships_run_until_the_max_generation() {
+        let glider = Format::Rle.parse("x = 8, y = 8\nbo$2bo$3o!").unwrap();
+        let (world, generation) = glider.clone().run_until_stable(12);
+
+        assert_eq!(world, glider.clone().tick_n(12, &glider.rule()));
+        assert_eq!(generation, None);
+    }
+}
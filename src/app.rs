@@ -1,31 +1,444 @@
-use crate::{cell::Cell, world::World};
+use crate::{
+    analysis::Cycle,
+    cell::{Cell, RenderMode},
+    engine::{EngineConfig, SimEngine},
+    library,
+    rule::Rule,
+    state, text,
+    world::{Downsample, EdgeBehavior, World},
+};
 use crossterm::cursor::{DisableBlinking, Hide};
 use crossterm::{
     cursor::{EnableBlinking, MoveTo, Show},
     event::{Event, KeyCode, KeyModifiers},
     execute,
-    style::{PrintStyledContent, Stylize},
-    terminal::{Clear, ClearType},
+    style::{Color, PrintStyledContent, StyledContent, Stylize},
+    terminal::{Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
+};
+use std::time::{Duration, Instant};
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    error::Error,
+    fmt::Display,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
 };
-use std::time::Duration;
-use std::{error::Error, io::Write};
+
+/// Writes a line of status text, like `writeln!`. Lines that are too wide for the terminal are cut off once the status
+/// text is shown, by `write_status_text`.
+macro_rules! status {
+    ($output:expr, $($arg:tt)*) => {
+        writeln!($output, $($arg)*)
+    };
+}
+
+/// How long to wait for input before moving on to the next frame, taking the ramp into account while simulating.
+fn frame_length(settings: &Settings, state: &State) -> Duration {
+    match state {
+        State::Simulate(simulate) => simulate.tick_length(settings),
+        _ => settings.tick_length,
+    }
+}
+
+/// Builds a `MoveTo` command for a position on the screen. Terminal coordinates are `u16`s, so positions that don't fit
+/// are clamped to the furthest possible position rather than being allowed to silently wrap around.
+fn move_to(x: usize, y: usize) -> MoveTo {
+    let clamp = |coordinate: usize| u16::try_from(coordinate).unwrap_or(u16::MAX);
+    MoveTo(clamp(x), clamp(y))
+}
+
+/// Writes the lines of status text for a frame. Any line wider than `columns` is cut off with an ellipsis, rather than
+/// being left to wrap and push the rest of the layout around. Nothing is cut off when `columns` is `None`, which is when
+/// the size of the terminal couldn't be found.
+fn write_status_text(
+    output: &mut impl Write,
+    status: &[u8],
+    columns: Option<usize>,
+) -> io::Result<()> {
+    let Some(columns) = columns else {
+        return output.write_all(status);
+    };
+
+    for line in String::from_utf8_lossy(status).lines() {
+        if text::visible_width(line) > columns {
+            let truncated = text::truncate_visible(line, columns.saturating_sub(1));
+            writeln!(output, "{}…", truncated)?;
+        } else {
+            writeln!(output, "{}", line)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The width of the terminal, which is looked up once for each frame's status text.
+fn terminal_columns() -> Option<usize> {
+    crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+}
 
 pub trait Component {
     type State;
     type Error;
 
-    fn display(&self, output: &mut impl Write) -> Result<(), Self::Error>;
-    fn update(self, message: Option<Event>) -> Result<Self::State, Self::Error>;
+    fn display(&self, output: &mut impl Write, settings: &Settings) -> Result<(), Self::Error>;
+    fn update(
+        self,
+        message: Option<Event>,
+        settings: &Settings,
+    ) -> Result<Self::State, Self::Error>;
 }
 
 pub struct App<'a, T> {
-    options: Options<'a, T>,
+    output: &'a mut T,
+    events: Option<Box<dyn Iterator<Item = Event> + 'a>>,
+    show_title: bool,
+    screen_mode: ScreenMode,
+    settings: Settings,
     state: State,
 }
 
 pub struct Options<'a, T> {
     pub output: &'a mut T,
     pub tick_length: Duration,
+    /// A world to start with, along with the mode to start it in. This skips `Scale` entirely, and when it's `None` the
+    /// app starts in `Scale` as usual. A world with no cells is rejected the same way, since the cursor would have
+    /// nowhere to go.
+    pub initial: Option<(World, StartMode)>,
+    /// Which corner of the grid is displayed as `(0, 0)`.
+    pub origin: Origin,
+    /// The rule used when simulating. This is usually Conway's rule, B3/S23.
+    pub rule: Rule,
+    /// The width of a permanently dead frame around the edge of the world while simulating. This is usually 0.
+    pub border: usize,
+    /// The size of the grid when starting in `Scale`. This is usually 8 by 8, or whatever size was last chosen.
+    pub initial_width: usize,
+    pub initial_height: usize,
+    /// Where to remember the grid size chosen in `Scale` for next time. Nothing is remembered when this is `None`.
+    pub size_file: Option<PathBuf>,
+    /// Gradually changes the tick length over the start of a simulation, rather than always using `tick_length`. This
+    /// is handy for demos, where the first few generations are usually the most interesting to watch.
+    pub ramp: Option<Ramp>,
+    /// Whether to show the generation and population in the terminal's title while simulating.
+    pub show_title: bool,
+    /// Whether to draw over the terminal's usual contents or to switch to its alternate screen, which is put away on
+    /// quitting so that whatever was there before comes back. This is `ScreenMode::Inline` by default.
+    pub screen_mode: ScreenMode,
+    /// Goes back to generation 0 after reaching this generation, forever, rather than simulating indefinitely. This is
+    /// handy for demos and screensavers that show off a particular pattern.
+    pub loop_after: Option<usize>,
+    /// Stops simulating for good once the world reaches this generation, rather than simulating indefinitely. This is
+    /// handy for demos that should end on their own, and for driving the app with `events` in tests. Going back to
+    /// generation 0 with `R` lets it run up to this generation again.
+    pub max_generations: Option<usize>,
+    /// How many generations to simulate for every frame that's shown, for watching patterns evolve faster than the
+    /// tick length allows. This can also be doubled and halved with `*` and `/` while simulating. It's capped at
+    /// `MAX_GENERATIONS_PER_FRAME`, and 0 is treated as 1.
+    pub generations_per_frame: usize,
+    /// Whether the cursor blinks while drawing, so that it's easy to find on a busy grid. It swaps between its usual
+    /// color and the same color as a background every half a second, rather than disappearing.
+    pub blink_cursor: bool,
+    /// How many cells apart gridlines are drawn while drawing, once they've been turned on with `G`. Gridlines aren't
+    /// available at all when this is 0.
+    pub grid_spacing: usize,
+    /// Whether to pause the simulation when the world settles into a still life or an oscillator, which is handy for
+    /// catching the moment that an unattended run settles down. This is off by default.
+    pub pause_on_cycle: bool,
+    /// The most live cells that a simulation can have before it's paused, as a safety valve for patterns that grow
+    /// without bound (such as breeders). A sparse world stores one entry per live cell, so this is what the budget
+    /// counts. There's no budget when this is `None`.
+    pub max_cells: Option<usize>,
+    /// Whether pressing `Enter` to start simulating a completely dead grid needs to be confirmed by pressing it again,
+    /// since that's usually a mistake. Turn this off when an empty grid is intentional.
+    pub confirm_empty: bool,
+    /// Whether to dim the first row and column of worlds that wrap around, to show where the edges join up. This can
+    /// also be toggled while simulating.
+    pub seam: bool,
+    /// Which characters cells are drawn with while drawing and simulating. This can also be switched with `V`.
+    pub render_mode: RenderMode,
+    /// How blocks of cells are shown while zoomed out with `-`.
+    pub downsample: Downsample,
+    /// Whether the status text goes underneath the world (the default) or above it. Putting it above keeps it on the
+    /// screen when the world is taller than the terminal.
+    pub status_position: StatusPosition,
+    /// Whether to pick colors for a dark background (the default) or a light one. `Theme::detect` can guess this for
+    /// terminals that say what their background is.
+    pub theme: Theme,
+    /// Where the world is saved to (in RLE format) when pressing `S`.
+    pub save_path: PathBuf,
+    /// The key that quits the app, which is `Ctrl` + `C` by default. It's checked before any of the states see the key,
+    /// so it always quits no matter what's happening, and it takes priority over anything else the key would do.
+    pub quit_key: QuitKey,
+    /// Whether to show the path of a saved file as an OSC 8 hyperlink. Not every terminal supports these, so this is
+    /// off by default and the plain path is shown instead.
+    pub hyperlinks: bool,
+    /// Events to feed to the app instead of reading them from the terminal, with one event being handled per frame. When
+    /// this is given the terminal isn't put into raw mode, and the app stops once the events run out. This is mostly
+    /// useful for driving the app from tests, with `output` being an in-memory buffer.
+    pub events: Option<Box<dyn Iterator<Item = Event> + 'a>>,
+}
+
+/// Describes a tick length that changes linearly from `start` to `end` over the first `generations` generations of a
+/// simulation, staying at `end` afterwards.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ramp {
+    pub start: Duration,
+    pub end: Duration,
+    pub generations: usize,
+}
+
+impl Ramp {
+    /// The tick length to use when at `generation`.
+    pub fn interval(&self, generation: usize) -> Duration {
+        if generation >= self.generations {
+            return self.end;
+        }
+
+        let progress = generation as f64 / self.generations as f64;
+        let (start, end) = (self.start.as_secs_f64(), self.end.as_secs_f64());
+
+        Duration::from_secs_f64(start + (end - start) * progress)
+    }
+}
+
+/// A key (along with any modifiers that need to be held) that quits the app.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuitKey {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl QuitKey {
+    /// Whether a key press should quit the app. Extra modifiers are allowed, since some terminals report `Shift` for
+    /// uppercase letters.
+    pub fn matches(&self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        code == self.code && modifiers.contains(self.modifiers)
+    }
+}
+
+impl Default for QuitKey {
+    fn default() -> Self {
+        QuitKey {
+            code: KeyCode::Char('c'),
+            modifiers: KeyModifiers::CONTROL,
+        }
+    }
+}
+
+/// Shows the key the same way as the hints in the status text, such as `Ctrl + C`.
+impl Display for QuitKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let modifiers = [
+            (KeyModifiers::CONTROL, "Ctrl"),
+            (KeyModifiers::ALT, "Alt"),
+            (KeyModifiers::SHIFT, "Shift"),
+        ];
+
+        for (modifier, name) in modifiers {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{} + ", name)?;
+            }
+        }
+
+        match self.code {
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::F(number) => write!(f, "F{}", number),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+/// Parses keys written like `ctrl+c`, `q`, `alt+x`, `esc`, or `f10`. Modifiers and key names are case insensitive, but
+/// a single letter is taken as it is.
+impl FromStr for QuitKey {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "invalid key `{}`, expected something like `ctrl+c` or `q`",
+                s
+            )
+        };
+        let mut parts: Vec<&str> = s.split('+').map(str::trim).collect();
+        let key = parts
+            .pop()
+            .filter(|key| !key.is_empty())
+            .ok_or_else(invalid)?;
+        let mut modifiers = KeyModifiers::NONE;
+
+        for part in parts {
+            modifiers |= match part.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(invalid()),
+            };
+        }
+
+        let mut chars = key.chars();
+
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match key.to_ascii_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "tab" => KeyCode::Tab,
+                function => function
+                    .strip_prefix('f')
+                    .and_then(|number| number.parse().ok())
+                    .filter(|number| (1..=12).contains(number))
+                    .map(KeyCode::F)
+                    .ok_or_else(invalid)?,
+            },
+        };
+
+        Ok(QuitKey { code, modifiers })
+    }
+}
+
+/// Which of the terminal's screens the app is drawn on.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ScreenMode {
+    /// The terminal's usual screen, which is cleared when starting. Whatever the app last drew is left behind after
+    /// quitting.
+    #[default]
+    Inline,
+    /// The terminal's alternate screen, which leaves the scrollback alone and goes back to the usual screen (as it was
+    /// before starting) after quitting.
+    AlternateScreen,
+}
+
+/// Where the status text goes relative to the world.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StatusPosition {
+    #[default]
+    Bottom,
+    Top,
+}
+
+/// Which colors to use, depending on whether the terminal has a dark or light background. Most of the UI uses the
+/// terminal's own foreground color, but highlights such as the cursor and the cells' shading need to be picked to stand
+/// out against the background.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Theme {
+    #[default]
+    Dark,
+    Light,
+}
+
+impl Theme {
+    /// Guesses the theme from the `COLORFGBG` environment variable, which some terminals set to the palette indexes of
+    /// their foreground and background colors, such as `15;0`. There's no way to ask the terminal itself, so this falls
+    /// back to `Theme::Dark` when the variable isn't set or doesn't make sense.
+    pub fn detect() -> Self {
+        let background = std::env::var("COLORFGBG")
+            .ok()
+            .and_then(|value| value.rsplit(';').next()?.parse::<u8>().ok());
+
+        match background {
+            // White, and the bright colors other than bright black.
+            Some(7 | 9..=15) => Theme::Light,
+            _ => Theme::Dark,
+        }
+    }
+
+    /// Swaps a bright highlight color for its darker counterpart on light backgrounds, where bright colors like yellow
+    /// are hard to make out. Colors that are already dark are left alone.
+    pub fn contrasting(self, color: Color) -> Color {
+        if self == Theme::Dark {
+            return color;
+        }
+
+        match color {
+            Color::Red => Color::DarkRed,
+            Color::Green => Color::DarkGreen,
+            Color::Yellow => Color::DarkYellow,
+            Color::Blue => Color::DarkBlue,
+            Color::Magenta => Color::DarkMagenta,
+            Color::Cyan => Color::DarkCyan,
+            color => color,
+        }
+    }
+
+    /// The background color for shading cells, such as locked cells and the trace.
+    pub fn shade(self) -> Color {
+        match self {
+            Theme::Dark => Color::DarkGrey,
+            Theme::Light => Color::Grey,
+        }
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dark" => Ok(Theme::Dark),
+            "light" => Ok(Theme::Light),
+            _ => Err(format!("unknown theme `{}`, expected `dark` or `light`", s)),
+        }
+    }
+}
+
+/// The mode to put a world given in `Options` into.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StartMode {
+    Draw,
+    Simulate,
+}
+
+/// The parts of `Options` that the states need access to. These stay the same for the whole run.
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub tick_length: Duration,
+    pub ramp: Option<Ramp>,
+    pub origin: Origin,
+    pub rule: Rule,
+    pub border: usize,
+    pub seam: bool,
+    pub render_mode: RenderMode,
+    pub downsample: Downsample,
+    pub status_position: StatusPosition,
+    pub theme: Theme,
+    pub pause_on_cycle: bool,
+    pub max_cells: Option<usize>,
+    pub confirm_empty: bool,
+    pub blink_cursor: bool,
+    pub grid_spacing: usize,
+    pub loop_after: Option<usize>,
+    pub max_generations: Option<usize>,
+    pub generations_per_frame: usize,
+    pub size_file: Option<PathBuf>,
+    pub save_path: PathBuf,
+    pub quit_key: QuitKey,
+    pub hyperlinks: bool,
+}
+
+/// The corner of the grid that is treated as `(0, 0)` when displaying it. Worlds are always stored with `(0, 0)` at the
+/// top-left, so this only affects rendering and how the cursor maps onto the world.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Origin {
+    #[default]
+    TopLeft,
+    BottomLeft,
+}
+
+impl Origin {
+    /// Converts between a row on the screen and a row in the world, for a world with the given height. Since this only
+    /// ever flips the Y axis, the same conversion works in both directions.
+    pub fn row(&self, row: usize, height: usize) -> usize {
+        match self {
+            Origin::TopLeft => row,
+            Origin::BottomLeft => height - 1 - row,
+        }
+    }
 }
 
 pub enum State {
@@ -37,18 +450,122 @@ pub enum State {
 pub struct Scale {
     width: usize,
     height: usize,
-    updated: bool,
 }
 
+/// The drawing editor. Editing actions that only ever touch one cell (such as a brush with a radius of 1) flip it, while
+/// actions that touch several cells at once (bigger brushes and stamps) set them all to the same state instead, so that
+/// overlapping cells can't end up being flipped twice. Once a state has been picked up with the eyedropper, the brush
+/// sets cells to that state instead of flipping them.
 pub struct Draw {
+    // The cursor position is stored in screen coordinates, and is converted when looking up cells in the world.
     x: usize,
     y: usize,
     world: World,
+    // How far the brush reaches from the cursor. A brush with a radius of 1 only covers the cell under the cursor.
+    brush: usize,
+    // The state picked up with the eyedropper, which the brush paints with. The brush flips cells when this is `None`.
+    paint_state: Option<Cell>,
+    // The rule to simulate with once we're done drawing.
+    rule: Rule,
+    editor: Option<RuleEditor>,
+    saved: Option<Saved>,
+    // The library pattern picked for stamping, as an index into `library::PATTERNS` along with the parsed pattern. The
+    // pattern is boxed so that it doesn't make every state bigger, like the engine in `Simulate`.
+    stamp: Option<(usize, Box<World>)>,
+    // The size of the last pattern pasted from the clipboard, or why it couldn't be pasted.
+    pasted: Option<Result<(usize, usize), String>>,
+    gridlines: bool,
+    // Set when `Enter` has been pressed on an empty grid, and needs to be pressed again to start simulating.
+    confirming_empty: bool,
+    // Whether to show the minimap when the world is too big to fit on the screen.
+    minimap: bool,
+    render_mode: RenderMode,
+    // How many cells across each character stands for. Worlds are shown as they are when this is 1.
+    zoom: usize,
+    // Which cells are protected from editing, indexed in the same order as the cells of `world`.
+    locks: Box<[bool]>,
+    // When a key was last pressed. The cursor's blinking is timed from here, so that it's always shown with its usual
+    // color straight after moving it or editing the cell under it.
+    blink_start: Instant,
 }
 
 pub struct Simulate {
-    generation: usize,
-    world: World,
+    // This is boxed since it's much bigger than the other states, and states get moved around every frame.
+    engine: Box<SimEngine>,
+    heatmap: bool,
+    seam: bool,
+    // Why the simulation is paused, or `None` if it's running.
+    pause: Option<Pause>,
+    // The simulation is paused while the rule editor is open.
+    editor: Option<RuleEditor>,
+    saved: Option<Saved>,
+    // The top-left corner of the viewport, in screen coordinates like the cursor in `Draw`.
+    scroll: (usize, usize),
+    minimap: bool,
+    render_mode: RenderMode,
+    zoom: usize,
+    generations_per_frame: usize,
+    // Which cells have been alive at any point since simulating started, indexed in the same order as the cells of the
+    // world. This is kept up to date even while it isn't shown, so that showing it includes the whole run.
+    trace: Box<[bool]>,
+    show_trace: bool,
+    // When each recent frame finished simulating and how many generations it simulated, going back `RATE_WINDOW`, for
+    // measuring the rate that's really being achieved. Big worlds can take longer to tick and draw than a tick lasts.
+    frames: VecDeque<(Instant, usize)>,
+    started: Instant,
+}
+
+/// The most generations that can be simulated for each frame.
+pub const MAX_GENERATIONS_PER_FRAME: usize = 1024;
+
+/// Roughly how many cells can be updated in a single frame. Big worlds simulate fewer generations per frame than asked
+/// for, so that the app stays responsive rather than seeming to hang.
+const CELLS_PER_FRAME: usize = 1 << 24;
+
+/// How far back to look when measuring how many generations are really being simulated each second.
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// How long the cursor spends in each of its two styles while blinking.
+const CURSOR_BLINK: Duration = Duration::from_millis(500);
+
+/// How many cells the viewport moves by each time an arrow key is pressed while simulating.
+const SCROLL_STEP: usize = 4;
+
+/// The most columns and rows that the minimap can take up. Bigger worlds are downsampled further to make them fit.
+const MINIMAP_SIZE: (usize, usize) = (24, 12);
+
+/// The outcome of the last attempt to save the world, being either the absolute path it was saved to or why it
+/// couldn't be saved.
+type Saved = Result<PathBuf, String>;
+
+/// The reason that a simulation is paused.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pause {
+    /// The user paused it.
+    Manual,
+    /// The world settled into this cycle, and `Settings::pause_on_cycle` is set.
+    Cycle(Cycle),
+    /// The world grew past this many live cells, which is `Settings::max_cells`.
+    Budget(usize),
+    /// The world reached `Settings::max_generations`. Resuming doesn't do anything, but resetting the world starts it
+    /// running again.
+    MaxGenerations(usize),
+}
+
+/// An overlay for editing a rule one neighbor count at a time. The digit keys toggle counts on and off, and the edited
+/// rule is only applied once it's confirmed.
+pub struct RuleEditor {
+    original: Rule,
+    rule: Rule,
+    // For birth/survival rules, whether the digit keys toggle survival counts rather than birth counts.
+    survival: bool,
+}
+
+/// What happened to a `RuleEditor` after handling a key press.
+pub enum Edit {
+    Editing(RuleEditor),
+    /// The editor was closed, leaving us with this rule. This is the original rule if editing was cancelled.
+    Closed(Rule),
 }
 
 impl<'a, T> App<'a, T>
@@ -56,83 +573,188 @@ where
     T: Write,
 {
     pub fn new(options: Options<'a, T>) -> Self {
-        App {
-            options,
-            state: State::Scale(Scale {
-                updated: true,
-                width: 8,
-                height: 8,
+        let settings = Settings {
+            tick_length: options.tick_length,
+            ramp: options.ramp,
+            origin: options.origin,
+            rule: options.rule,
+            border: options.border,
+            seam: options.seam,
+            render_mode: options.render_mode,
+            downsample: options.downsample,
+            pause_on_cycle: options.pause_on_cycle,
+            max_cells: options.max_cells,
+            confirm_empty: options.confirm_empty,
+            blink_cursor: options.blink_cursor,
+            grid_spacing: options.grid_spacing,
+            loop_after: options.loop_after,
+            max_generations: options.max_generations,
+            generations_per_frame: options
+                .generations_per_frame
+                .clamp(1, MAX_GENERATIONS_PER_FRAME),
+            size_file: options.size_file,
+            save_path: options.save_path,
+            quit_key: options.quit_key,
+            status_position: options.status_position,
+            theme: options.theme,
+            hyperlinks: options.hyperlinks,
+        };
+
+        // A world with no cells can't be drawn or shown, so it's given up on in favour of `Scale`.
+        let initial = options
+            .initial
+            .filter(|(world, _)| world.width() > 0 && world.height() > 0);
+
+        let state = match initial {
+            Some((world, StartMode::Draw)) => {
+                State::Draw(Draw::new(world, settings.rule, &settings))
+            }
+            Some((world, StartMode::Simulate)) => {
+                State::Simulate(Simulate::new(world, settings.rule, &settings))
+            }
+            None => State::Scale(Scale {
+                // A grid must always be at least 1 by 1, so that the cursor has somewhere to go in `Draw`.
+                width: options.initial_width.max(1),
+                height: options.initial_height.max(1),
             }),
+        };
+
+        App {
+            output: options.output,
+            events: options.events,
+            show_title: options.show_title,
+            screen_mode: options.screen_mode,
+            settings,
+            state,
         }
     }
 
     pub fn run(self) -> Result<(), Box<dyn Error>> {
         // This is done to get around a weird issue relating to moved values (even though the moved fields are disjoint)
         let mut state = self.state;
-        let options = self.options;
-
-        crossterm::terminal::enable_raw_mode()?;
-        execute!(options.output, Clear(ClearType::All), DisableBlinking, Hide)?;
-
-        loop {
-            state.display(options.output)?;
-            let event = crossterm::event::poll(options.tick_length)?
-                .then(|| crossterm::event::read().ok())
-                .flatten();
-
-            match state.update(event)? {
-                Some(new_state) => state = new_state,
-                None => {
-                    execute!(options.output, EnableBlinking, Show)?;
-                    crossterm::terminal::disable_raw_mode()?;
-                    std::process::exit(0)
+        let mut events = self.events;
+        let output = self.output;
+        let settings = self.settings;
+
+        // Scripted events don't come from the terminal, so there's no need to take it over.
+        let interactive = events.is_none();
+
+        if interactive {
+            crossterm::terminal::enable_raw_mode()?;
+        }
+
+        let alternate_screen = interactive && self.screen_mode == ScreenMode::AlternateScreen;
+
+        if alternate_screen {
+            execute!(output, EnterAlternateScreen)?;
+        }
+
+        execute!(output, Clear(ClearType::All), DisableBlinking, Hide)?;
+
+        // The last title we set, so that we only send it to the terminal when it actually changes.
+        let mut title = None;
+
+        // Each frame is put together in here and then written out all at once, which is much quicker than lots of small
+        // writes (especially over SSH), and stops the terminal from showing half-drawn frames. This is kept between
+        // frames so that it only needs to grow once.
+        let mut frame = Vec::new();
+
+        'run: loop {
+            if self.show_title {
+                let new_title = match &state {
+                    State::Simulate(simulate) => Some(simulate.title()),
+                    _ => None,
+                };
+
+                if new_title != title {
+                    execute!(output, SetTitle(new_title.as_deref().unwrap_or("")))?;
+                    title = new_title;
+                }
+            }
+
+            frame.clear();
+            state.display(&mut frame, &settings)?;
+            output.write_all(&frame)?;
+            output.flush()?;
+
+            let mut event = match &mut events {
+                Some(events) => match events.next() {
+                    Some(event) => Some(event),
+                    None => break,
+                },
+                None => crossterm::event::poll(frame_length(&settings, &state))?
+                    .then(|| crossterm::event::read().ok())
+                    .flatten(),
+            };
+
+            loop {
+                match state.update(event, &settings)? {
+                    Some(new_state) => state = new_state,
+                    None => break 'run,
+                }
+
+                // Holding a key down can queue up events faster than we render them. States that don't tick handle
+                // everything that's already waiting before rendering again, so that the cursor doesn't keep moving
+                // after the key is let go. Simulating still handles one event per frame, since each update is a tick.
+                let drain = interactive && !matches!(state, State::Simulate(_));
+
+                if !drain || !crossterm::event::poll(Duration::ZERO)? {
+                    break;
                 }
+
+                event = crossterm::event::read().ok();
             }
         }
+
+        if title.is_some() {
+            execute!(output, SetTitle(""))?;
+        }
+
+        execute!(output, EnableBlinking, Show)?;
+
+        if alternate_screen {
+            execute!(output, LeaveAlternateScreen)?;
+        }
+
+        if interactive {
+            crossterm::terminal::disable_raw_mode()?;
+        }
+
+        Ok(())
     }
 }
 
 impl Component for State {
-    // This is `Option<State>` to represent us receiving a `Ctrl` + `C` input and needing to exit.
+    // This is `Option<State>` to represent us receiving the quit key and needing to exit.
     type State = Option<State>;
     type Error = Box<dyn Error>;
 
-    fn display(&self, output: &mut impl Write) -> Result<(), Self::Error> {
+    fn display(&self, output: &mut impl Write, settings: &Settings) -> Result<(), Self::Error> {
         execute!(output, MoveTo(0, 0))?;
 
         match self {
-            State::Scale(scale) => scale.display(output),
-            State::Draw(draw) => draw.display(output),
-            State::Simulate(simulate) => simulate.display(output),
-        }?;
-
-        writeln!(
-            output,
-            "{} + {}: Quit",
-            "Ctrl".blue().bold(),
-            "C".blue().bold()
-        )?;
-
-        output.flush()?;
-
-        Ok(())
+            State::Scale(scale) => scale.display(output, settings),
+            State::Draw(draw) => draw.display(output, settings),
+            State::Simulate(simulate) => simulate.display(output, settings),
+        }
     }
 
-    fn update(self, message: Option<Event>) -> Result<Option<State>, Self::Error> {
+    fn update(
+        self,
+        message: Option<Event>,
+        settings: &Settings,
+    ) -> Result<Option<State>, Self::Error> {
         if let Some(Event::Key(press)) = message {
-            // Regardless of our current state, we need to handle a `Ctrl` + `C` and exit.
-            let is_ctrl = press.modifiers.contains(KeyModifiers::CONTROL);
-            let is_c = matches!(press.code, KeyCode::Char('c'));
-
-            if is_ctrl && is_c {
+            // Regardless of our current state, we need to handle the quit key and exit.
+            if settings.quit_key.matches(press.code, press.modifiers) {
                 return Ok(None);
             }
         }
 
         match self {
-            State::Scale(scale) => scale.update(message),
-            State::Draw(draw) => draw.update(message),
-            State::Simulate(simulate) => simulate.update(message),
+            State::Scale(scale) => scale.update(message, settings),
+            State::Draw(draw) => draw.update(message, settings),
+            State::Simulate(simulate) => simulate.update(message, settings),
         }
         .map(Some)
     }
@@ -142,67 +764,77 @@ impl Component for Scale {
     type State = State;
     type Error = Box<dyn Error>;
 
-    fn display(&self, output: &mut impl Write) -> Result<(), Self::Error> {
-        if self.updated {
-            execute!(output, Clear(ClearType::FromCursorDown))?;
+    fn display(&self, output: &mut impl Write, settings: &Settings) -> Result<(), Self::Error> {
+        let mut status = Vec::new();
+        self.write_status_lines(&mut status, settings)?;
 
-            for row_index in 0..self.height {
-                for _ in 0..self.width {
-                    write!(output, "{}", Cell::Dead.block())?;
-                }
+        let top = write_status_above(output, &status, settings)?;
+        execute!(output, move_to(0, top), Clear(ClearType::FromCursorDown))?;
 
-                if row_index + 1 < self.height {
-                    write!(output, "\n")?;
-                }
+        for row_index in 0..self.height {
+            for _ in 0..self.width {
+                write!(output, "{}", Cell::Dead.block())?;
             }
 
-            execute!(output, MoveTo(0, (self.height + 1) as u16),)?;
-            writeln!(output, "Currently in {} mode", "Scale".bold().cyan(),)?;
-            writeln!(
-                output,
-                "The grid is currently {} cell(s) wide and {} cell(s) high",
-                self.width.to_string().bold(),
-                self.height.to_string().bold(),
-            )?;
-
-            writeln!(output, "{}: Change grid size", "↑↓←→".blue().bold())?;
-            writeln!(output, "{}: Start drawing", "Enter".blue().bold())?;
+            if row_index + 1 < self.height {
+                writeln!(output)?;
+            }
         }
 
-        Ok(())
+        Ok(write_status_below(
+            output,
+            &status,
+            settings,
+            top,
+            self.height,
+        )?)
     }
 
-    fn update(mut self, message: Option<Event>) -> Result<State, Self::Error> {
+    fn update(mut self, message: Option<Event>, settings: &Settings) -> Result<State, Self::Error> {
         let press = match message {
             Some(Event::Key(press)) => press,
             _ => return Ok(State::Scale(self)),
         };
 
-        self.updated = matches!(
-            press.code,
-            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right
-        );
+        match press.code {
+            KeyCode::Up => self.height = self.height.saturating_sub(1).max(1),
+            KeyCode::Down => self.height += 1,
+            KeyCode::Left => self.width = self.width.saturating_sub(1).max(1),
+            KeyCode::Right => self.width += 1,
+            KeyCode::Enter => {
+                if let Some(path) = &settings.size_file {
+                    // There's nowhere to report an error while the terminal is taken over, and forgetting the size
+                    // isn't worth stopping for, so failures are ignored.
+                    let _ = state::save_size(path, (self.width, self.height));
+                }
 
-        if self.updated {
-            match press.code {
-                KeyCode::Up => self.height = (self.height - 1).max(1),
-                KeyCode::Down => self.height += 1,
-                KeyCode::Left => self.width = (self.width - 1).max(1),
-                KeyCode::Right => self.width += 1,
-                _ => unreachable!(),
+                let world = World::new(self.width, self.height);
+                return Ok(State::Draw(Draw::new(world, settings.rule, settings)));
             }
+            _ => {}
         }
 
-        let state = match press.code {
-            KeyCode::Enter => State::Draw(Draw {
-                x: 0,
-                y: 0,
-                world: World::new(self.width, self.height),
-            }),
-            _ => State::Scale(self),
-        };
+        Ok(State::Scale(self))
+    }
+}
 
-        Ok(state)
+impl Scale {
+    fn write_status_lines(&self, output: &mut impl Write, settings: &Settings) -> io::Result<()> {
+        status!(
+            output,
+            "Currently in {} mode",
+            "Scale".bold().with(settings.theme.contrasting(Color::Cyan))
+        )?;
+        status!(
+            output,
+            "The grid is currently {} cell(s) wide and {} cell(s) high",
+            self.width.to_string().bold(),
+            self.height.to_string().bold(),
+        )?;
+
+        status!(output, "{}: Change grid size", "↑↓←→".blue().bold())?;
+        status!(output, "{}: Start drawing", "Enter".blue().bold())?;
+        write_quit(output, settings)
     }
 }
 
@@ -210,47 +842,187 @@ impl Component for Draw {
     type State = State;
     type Error = Box<dyn Error>;
 
-    fn display(&self, output: &mut impl Write) -> Result<(), Self::Error> {
-        writeln!(output, "{}", self.world)?;
-        execute!(
+    fn display(&self, output: &mut impl Write, settings: &Settings) -> Result<(), Self::Error> {
+        let shown = zoomed(&self.world, self.zoom, settings.downsample);
+        let gridlines = self.gridlines(settings);
+        let (layout, status) = self.layout(&shown, settings)?;
+        let viewport = layout.viewport;
+        let top = write_status_above(output, &status, settings)?;
+        let screen = |position| {
+            let (x, y) = viewport.screen(gridlines, self.zoomed_position(position, settings));
+            (x, y + top)
+        };
+        let (x, y) = screen(self.clamped_cursor());
+        let style = cell_style(&shown, settings.seam, self.render_mode);
+
+        execute!(output, move_to(0, top))?;
+        write_world(
             output,
-            MoveTo(self.x as u16, self.y as u16),
-            PrintStyledContent(match self.world.get((self.x, self.y)).unwrap() {
-                Cell::Alive => "o".green(),
-                Cell::Dead => "o".red(),
-            }),
-            MoveTo(0, (self.world.height() + 1) as u16),
-            Clear(ClearType::FromCursorDown)
+            &shown,
+            settings.origin,
+            viewport,
+            gridlines,
+            |position, cell| {
+                // Locks are left out while zoomed out, since each character covers lots of cells.
+                if self.zoom == 1 && self.locked(position) {
+                    style(position, cell).on(settings.theme.shade())
+                } else {
+                    style(position, cell)
+                }
+            },
         )?;
 
-        writeln!(output, "Currently in {} mode", "Drawing".bold().yellow())?;
-        writeln!(output, "{}: Flip cell under cursor", "Space".blue().bold())?;
-        writeln!(output, "{}: Move cursor", "↑↓←→".blue().bold())?;
-        writeln!(output, "{}: Start simulating", "Enter".blue().bold())?;
+        let (columns, mut rows) = viewport.screen_size(gridlines);
 
-        Ok(())
+        if layout.minimap {
+            rows = rows.max(write_minimap(
+                output,
+                &shown,
+                settings.origin,
+                viewport,
+                (columns + 1, top),
+            )?);
+        }
+
+        for position in self.stamp_preview() {
+            if !viewport.contains(self.zoomed_position(position, settings)) {
+                continue;
+            }
+
+            let (stamp_x, stamp_y) = screen(position);
+
+            execute!(
+                output,
+                move_to(stamp_x, stamp_y),
+                PrintStyledContent(
+                    Cell::Alive
+                        .glyph(self.render_mode)
+                        .with(settings.theme.contrasting(Color::Cyan))
+                )
+            )?;
+        }
+
+        let cursor = "o".with(settings.theme.contrasting(
+            match self.world.get(self.cursor(settings)).unwrap() {
+                Cell::Alive => Color::Green,
+                Cell::Dead => Color::Red,
+                Cell::Marker => Color::Yellow,
+            },
+        ));
+
+        // This goes by how long it's been rather than by counting frames, since frames aren't evenly spaced while
+        // drawing. Only the way the cursor is shown changes, so the cell underneath is never touched.
+        let phase = self.blink_start.elapsed().as_millis() / CURSOR_BLINK.as_millis();
+        let blinked = settings.blink_cursor && phase % 2 == 1;
+
+        execute!(
+            output,
+            move_to(x, y),
+            PrintStyledContent(if blinked { cursor.reverse() } else { cursor })
+        )?;
+
+        Ok(write_status_below(output, &status, settings, top, rows)?)
     }
 
-    fn update(mut self, message: Option<Event>) -> Result<State, Self::Error> {
+    fn update(mut self, message: Option<Event>, settings: &Settings) -> Result<State, Self::Error> {
+        self.clamp_cursor();
+        self.fit_locks();
+
         let press = match message {
             Some(Event::Key(press)) => press,
             _ => return Ok(State::Draw(self)),
         };
 
+        self.blink_start = Instant::now();
+
+        if let Some(editor) = self.editor.take() {
+            match editor.update(press.code) {
+                Edit::Editing(editor) => self.editor = Some(editor),
+                Edit::Closed(rule) => self.rule = rule,
+            }
+
+            return Ok(State::Draw(self));
+        }
+
+        // Any key other than `Enter` backs out of starting an empty simulation.
+        let confirming_empty = std::mem::take(&mut self.confirming_empty);
+
         match press.code {
-            KeyCode::Up => self.y = self.y.saturating_sub(1),
-            KeyCode::Down => self.y = (self.y + 1).min(self.world.height() - 1),
-            KeyCode::Left => self.x = self.x.saturating_sub(1),
-            KeyCode::Right => self.x = (self.x + 1).min(self.world.width() - 1),
-            KeyCode::Char(' ') => self.world.get_mut((self.x, self.y)).unwrap().flip(),
+            // While zoomed out, the cursor moves a whole block at a time so that it moves by one character on the screen.
+            KeyCode::Up => self.y = self.y.saturating_sub(self.zoom),
+            KeyCode::Down => self.y = (self.y + self.zoom).min(self.world.height() - 1),
+            KeyCode::Left => self.x = self.x.saturating_sub(self.zoom),
+            KeyCode::Right => self.x = (self.x + self.zoom).min(self.world.width() - 1),
+            KeyCode::Char('+' | '=') => self.zoom = zoom_in(self.zoom),
+            KeyCode::Char('-') => self.zoom = zoom_out(self.zoom, &self.world),
+            KeyCode::Char(' ') => self.flip_brush(settings),
+            // The eyedropper would usually be on G, but that already toggles gridlines.
+            KeyCode::Char('y') => self.paint_state = self.world.get(self.cursor(settings)),
+            KeyCode::Char('Y') => self.paint_state = None,
+            KeyCode::Char('[') => self.brush = (self.brush - 1).max(1),
+            // A brush as big as the world already covers all of it from anywhere, so there's no use going bigger.
+            KeyCode::Char(']') => {
+                self.brush =
+                    (self.brush + 1).min(self.world.width().max(self.world.height()).max(1))
+            }
+            KeyCode::Char('i') => {
+                let flip = |position, cell: Cell| {
+                    if self.locked(position) {
+                        cell
+                    } else {
+                        cell.flipped()
+                    }
+                };
+
+                self.world = self.world.map(flip);
+            }
+            KeyCode::Char('l') => {
+                let (x, y) = self.cursor(settings);
+                if let Some(lock) = self.locks.get_mut(y * self.world.width() + x) {
+                    *lock = !*lock;
+                }
+            }
+            KeyCode::Char('L') => self.locks.fill(false),
+            KeyCode::Char(',' | '<') => self.select_stamp(library::PATTERNS.len() - 1),
+            KeyCode::Char('.' | '>') => self.select_stamp(1),
+            KeyCode::Char('p') => self.apply_stamp(settings),
+            KeyCode::Esc => self.stamp = None,
+            KeyCode::Char('g') => self.gridlines = !self.gridlines,
+            KeyCode::Char('n') => self.minimap = !self.minimap,
+            #[cfg(feature = "clipboard")]
+            KeyCode::Char('v') if press.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.paste(settings)
+            }
+            KeyCode::Char('v') => self.render_mode = self.render_mode.next(),
+            KeyCode::Char('m') if !self.locked(self.cursor(settings)) => {
+                let cursor = self.cursor(settings);
+                let cell = &mut self.world[cursor];
+                *cell = if cell.marker() {
+                    Cell::Dead
+                } else {
+                    Cell::Marker
+                };
+            }
+            KeyCode::Char('u') => self.editor = Some(RuleEditor::new(self.rule)),
+            KeyCode::Char('s') => {
+                self.saved = Some(save(&self.world, self.rule, &settings.save_path))
+            }
             _ => {}
         };
 
         let state = match press.code {
-            KeyCode::Enter => State::Simulate(Simulate {
-                generation: 0,
-                world: self.world,
-            }),
+            KeyCode::Enter
+                if settings.confirm_empty && !confirming_empty && self.world.population() == 0 =>
+            {
+                self.confirming_empty = true;
+                State::Draw(self)
+            }
+            KeyCode::Enter => {
+                let mut simulate = Simulate::new(self.world, self.rule, settings);
+                simulate.render_mode = self.render_mode;
+                simulate.zoom = self.zoom;
+                State::Simulate(simulate)
+            }
             _ => State::Draw(self),
         };
 
@@ -258,37 +1030,1663 @@ impl Component for Draw {
     }
 }
 
-impl Component for Simulate {
-    type State = State;
-    type Error = Box<dyn Error>;
+impl Draw {
+    fn new(world: World, rule: Rule, settings: &Settings) -> Self {
+        let locks = vec![false; world.width() * world.height()].into_boxed_slice();
 
-    fn display(&self, output: &mut impl Write) -> Result<(), Self::Error> {
-        writeln!(output, "{}", self.world)?;
-        execute!(
+        Draw {
+            locks,
+            x: 0,
+            y: 0,
+            world,
+            brush: 1,
+            paint_state: None,
+            rule,
+            editor: None,
+            saved: None,
+            stamp: None,
+            pasted: None,
+            gridlines: false,
+            confirming_empty: false,
+            minimap: true,
+            render_mode: settings.render_mode,
+            zoom: 1,
+            blink_start: Instant::now(),
+        }
+    }
+
+    /// Lays out `shown` (the world as it is at the current zoom level) with the viewport centered on the cursor, along
+    /// with the status text to show underneath it.
+    fn layout(
+        &self,
+        shown: &World,
+        settings: &Settings,
+    ) -> Result<(Layout, Vec<u8>), Box<dyn Error>> {
+        let cursor = self.zoomed_position(self.clamped_cursor(), settings);
+
+        Layout::new(
+            shown,
+            self.gridlines(settings),
+            self.minimap,
+            |room| Viewport::around(shown, room, cursor),
+            |output, scrolling| {
+                self.write_status_lines(output, settings, scrolling)?;
+                Ok(write_quit(output, settings)?)
+            },
+        )
+    }
+
+    /// Converts a position on the grid (with the row in screen order, like the cursor) into the position of the block
+    /// it's in at the current zoom level.
+    fn zoomed_position(&self, (x, row): (usize, usize), settings: &Settings) -> (usize, usize) {
+        let height = self.world.height();
+        let y = settings.origin.row(row, height);
+        let y = settings
+            .origin
+            .row(y / self.zoom, height.div_ceil(self.zoom));
+        (x / self.zoom, y)
+    }
+
+    /// Writes the status text shown underneath the world, including the hints for the minimap if the world is too
+    /// big to fit on the screen.
+    fn write_status_lines(
+        &self,
+        output: &mut impl Write,
+        settings: &Settings,
+        scrolling: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        if let Some(editor) = &self.editor {
+            return Ok(editor.display(output)?);
+        }
+
+        let highlight = settings.theme.contrasting(Color::Yellow);
+        status!(
             output,
-            MoveTo(0, (self.world.height() + 1) as u16),
-            Clear(ClearType::FromCursorDown)
+            "Currently in {} mode",
+            "Drawing".bold().with(highlight)
         )?;
 
-        writeln!(
+        if self.confirming_empty {
+            status!(
+                output,
+                "{} — press {} again to simulate",
+                "Grid is empty".bold().with(highlight),
+                "Enter".blue().bold()
+            )?;
+        }
+
+        write_pattern_name(output, &self.world)?;
+        write_saved(output, &self.saved, settings)?;
+
+        match &self.pasted {
+            Some(Ok((width, height))) => status!(
+                output,
+                "Pasted a {} pattern from the clipboard",
+                format!("{}x{}", width, height).bold()
+            )?,
+            Some(Err(error)) => status!(output, "{}", error.as_str().red())?,
+            None => {}
+        }
+
+        status!(
             output,
-            "Currently in {} mode",
-            "Simulation".bold().magenta()
+            "Cell under cursor has {} live neighbor(s)",
+            self.world
+                .live_neighbors(self.cursor(settings))
+                .to_string()
+                .bold()
+        )?;
+        status!(
+            output,
+            "The brush currently has a radius of {}",
+            self.brush.to_string().bold()
         )?;
+        match self.paint_state {
+            Some(state) => status!(
+                output,
+                "{}: Paint {} cells under brush, {}: Go back to flipping cells",
+                "Space".blue().bold(),
+                state.name().bold(),
+                "Shift + Y".blue().bold()
+            )?,
+            None => status!(
+                output,
+                "{}: Paint or erase under brush",
+                "Space".blue().bold()
+            )?,
+        }
 
-        writeln!(
+        status!(
             output,
-            "Currently at generation #{}",
-            self.generation.to_string().bold()
+            "{}: Pick up the state of the cell under the cursor to paint with",
+            "Y".blue().bold()
         )?;
+        status!(output, "{}: Change brush size", "[]".blue().bold())?;
+        status!(output, "{}: Move cursor", "↑↓←→".blue().bold())?;
 
-        Ok(())
-    }
+        if scrolling {
+            status!(output, "{}: Toggle minimap", "N".blue().bold())?;
+        }
+
+        status!(output, "{}: Invert grid", "I".blue().bold())?;
+        status!(output, "{}: Place or remove marker", "M".blue().bold())?;
+        status!(
+            output,
+            "{}: Lock or unlock cell, {}: Unlock everything",
+            "L".blue().bold(),
+            "Shift + L".blue().bold()
+        )?;
 
-    fn update(mut self, _: Option<Event>) -> Result<State, Self::Error> {
-        self.world = self.world.tick();
-        self.generation += 1;
+        match self.locks.iter().filter(|&&locked| locked).count() {
+            0 => {}
+            count => status!(output, "{} cell(s) are locked", count.to_string().bold())?,
+        }
 
-        Ok(State::Simulate(self))
+        if settings.grid_spacing > 0 {
+            status!(output, "{}: Toggle gridlines", "G".blue().bold())?;
+        }
+
+        status!(
+            output,
+            "{}: Change rendering (currently {})",
+            "V".blue().bold(),
+            self.render_mode.name()
+        )?;
+        write_zoom(output, self.zoom)?;
+
+        match &self.stamp {
+            Some((index, _)) => {
+                status!(
+                    output,
+                    "{}: Stamp {} at cursor, {}: Put it away",
+                    "P".blue().bold(),
+                    library::PATTERNS[*index].name.bold(),
+                    "Esc".blue().bold()
+                )?;
+                status!(output, "{}: Choose another pattern", "<>".blue().bold())?;
+            }
+            None => status!(output, "{}: Choose a pattern to stamp", "<>".blue().bold())?,
+        }
+
+        status!(output, "{}: Save pattern", "S".blue().bold())?;
+
+        if cfg!(feature = "clipboard") {
+            status!(
+                output,
+                "{}: Paste a pattern from the clipboard",
+                "Ctrl + V".blue().bold()
+            )?;
+        }
+
+        status!(
+            output,
+            "{}: Edit rule (currently {})",
+            "U".blue().bold(),
+            self.rule
+        )?;
+        status!(output, "{}: Start simulating", "Enter".blue().bold())?;
+
+        Ok(())
+    }
+
+    /// The gridlines to draw over the world, if they're turned on.
+    fn gridlines(&self, settings: &Settings) -> Option<Gridlines> {
+        // The lines wouldn't line up with anything once the world is zoomed out.
+        (self.gridlines && settings.grid_spacing > 0 && self.zoom == 1).then_some(Gridlines {
+            spacing: settings.grid_spacing,
+            origin: settings.origin,
+            height: self.world.height(),
+        })
+    }
+
+    /// Moves the stamp selection forwards through the library by `step` patterns, wrapping around at the end. When
+    /// nothing has been picked yet, this picks either the first or the last pattern.
+    fn select_stamp(&mut self, step: usize) {
+        let count = library::PATTERNS.len();
+        let index = match &self.stamp {
+            Some((index, _)) => (index + step) % count,
+            None if step == 1 => 0,
+            None => count - 1,
+        };
+
+        self.stamp = Some((index, Box::new(library::PATTERNS[index].world())));
+    }
+
+    /// The screen positions of the cells that stamping the picked pattern would bring to life.
+    fn stamp_preview(&self) -> Vec<(usize, usize)> {
+        match &self.stamp {
+            Some((_, pattern)) => self.placed_cells(pattern),
+            None => Vec::new(),
+        }
+    }
+
+    /// The screen positions of the live cells of `pattern`, when it's placed with its top-left corner at the cursor as
+    /// it appears on screen. Any part of it that hangs off the edge of the world is clipped.
+    fn placed_cells(&self, pattern: &World) -> Vec<(usize, usize)> {
+        let (x, y) = self.clamped_cursor();
+
+        pattern
+            .live_cells()
+            .map(|(pattern_x, pattern_y)| (x + pattern_x, y + pattern_y))
+            .filter(|&(x, y)| x < self.world.width() && y < self.world.height())
+            .collect()
+    }
+
+    /// Brings the cells at the given screen positions to life, apart from any that are locked. Cells that are already
+    /// alive stay alive, rather than being flipped.
+    fn bring_to_life(&mut self, cells: Vec<(usize, usize)>, settings: &Settings) {
+        let height = self.world.height();
+        let cells: Vec<_> = cells
+            .into_iter()
+            .map(|(x, y)| (x, settings.origin.row(y, height)))
+            .filter(|&position| !self.locked(position))
+            .collect();
+
+        self.world.set_region(cells, Cell::Alive);
+    }
+
+    /// Brings the cells under the stamp to life.
+    fn apply_stamp(&mut self, settings: &Settings) {
+        self.bring_to_life(self.stamp_preview(), settings);
+    }
+
+    /// Reads an RLE or plaintext pattern from the system clipboard and brings its live cells to life, placed like a
+    /// stamp. If the clipboard can't be read or doesn't hold a pattern, the reason is shown instead.
+    #[cfg(feature = "clipboard")]
+    fn paste(&mut self, settings: &Settings) {
+        self.paste_text(crate::clipboard::read_text(), settings)
+    }
+
+    /// The part of `paste` that happens once the clipboard has been read.
+    #[cfg(feature = "clipboard")]
+    fn paste_text(&mut self, text: std::io::Result<String>, settings: &Settings) {
+        use crate::format::Format;
+
+        let pattern = text
+            .map_err(|error| format!("Couldn't read the clipboard: {}", error))
+            .and_then(|text| {
+                Format::detect(&text)
+                    .parse(&text)
+                    .map_err(|error| format!("Couldn't paste the clipboard: {}", error))
+            });
+
+        self.pasted = Some(pattern.map(|pattern| {
+            self.bring_to_life(self.placed_cells(&pattern), settings);
+            (pattern.width(), pattern.height())
+        }));
+    }
+
+    /// Applies the brush at the cursor. A brush with a radius of 1 just flips the cell under the cursor, and bigger
+    /// brushes set every cell they cover to the flipped state of that cell, so that they either paint or erase. When a
+    /// state has been picked up with the eyedropper, every cell the brush covers is set to it instead. Any part of the
+    /// brush that hangs off the edge of the world is ignored.
+    fn flip_brush(&mut self, settings: &Settings) {
+        let (x, y) = self.cursor(settings);
+        let reach = self.brush - 1;
+
+        let Some(cell) = self.world.get((x, y)) else {
+            return;
+        };
+
+        if reach == 0 && self.paint_state.is_none() {
+            if !self.locked((x, y)) {
+                self.world.toggle((x, y));
+            }
+
+            return;
+        }
+
+        let columns = x.saturating_sub(reach)..=x.saturating_add(reach).min(self.world.width() - 1);
+        let rows = y.saturating_sub(reach)..=y.saturating_add(reach).min(self.world.height() - 1);
+        let positions: Vec<_> = rows
+            .flat_map(|brush_y| columns.clone().map(move |brush_x| (brush_x, brush_y)))
+            .filter(|&position| !self.locked(position))
+            .collect();
+
+        self.world.set_region(
+            positions,
+            self.paint_state.unwrap_or_else(|| cell.flipped()),
+        );
+    }
+
+    /// Whether the cell at a position in the world is locked. Locked cells aren't changed by anything in the editor,
+    /// but they're simulated like any other cell.
+    fn locked(&self, (x, y): (usize, usize)) -> bool {
+        x < self.world.width() && self.locks.get(y * self.world.width() + x) == Some(&true)
+    }
+
+    /// The position of the cursor within the world.
+    fn cursor(&self, settings: &Settings) -> (usize, usize) {
+        let (x, y) = self.clamped_cursor();
+        (x, settings.origin.row(y, self.world.height()))
+    }
+
+    /// The position of the cursor on the screen, moved back inside the world if the world has shrunk since the cursor
+    /// was last moved.
+    fn clamped_cursor(&self) -> (usize, usize) {
+        (
+            self.x.min(self.world.width().saturating_sub(1)),
+            self.y.min(self.world.height().saturating_sub(1)),
+        )
+    }
+
+    fn clamp_cursor(&mut self) {
+        (self.x, self.y) = self.clamped_cursor();
+    }
+
+    /// Clears the locks if the world has changed size since they were made, since they'd no longer line up with its
+    /// cells.
+    fn fit_locks(&mut self) {
+        let cells = self.world.width() * self.world.height();
+
+        if self.locks.len() != cells {
+            self.locks = vec![false; cells].into_boxed_slice();
+        }
+    }
+}
+
+impl Component for Simulate {
+    type State = State;
+    type Error = Box<dyn Error>;
+
+    fn display(&self, output: &mut impl Write, settings: &Settings) -> Result<(), Self::Error> {
+        let shown = zoomed(self.engine.world(), self.zoom, settings.downsample);
+        let world = shown.as_ref();
+        let (layout, status) = self.layout(world, settings)?;
+        let viewport = layout.viewport;
+        let top = write_status_above(output, &status, settings)?;
+
+        execute!(output, move_to(0, top))?;
+
+        if self.heatmap {
+            // Each cell is colored by how many live neighbors it has, rather than by whether it's alive. This always uses
+            // the classic characters, since the colors of dead cells would be invisible if they were blank.
+            let counts = world.neighbor_counts();
+            let style = |(x, y): (usize, usize), cell: Cell| {
+                let color = heat_color(counts[y * world.width() + x]);
+                cell.block().with(settings.theme.contrasting(color))
+            };
+
+            write_world(output, world, settings.origin, viewport, None, style)?;
+        } else {
+            let style = cell_style(world, self.seam, self.render_mode);
+
+            write_world(
+                output,
+                world,
+                settings.origin,
+                viewport,
+                None,
+                |(x, y), cell| {
+                    // The trace is left out while zoomed out, since each character covers lots of cells.
+                    let traced =
+                        self.show_trace && self.zoom == 1 && self.trace[y * world.width() + x];
+
+                    if traced && !cell.alive() {
+                        style((x, y), cell).on(settings.theme.shade())
+                    } else {
+                        style((x, y), cell)
+                    }
+                },
+            )?;
+        }
+
+        let (columns, mut rows) = viewport.screen_size(None);
+
+        if layout.minimap {
+            rows = rows.max(write_minimap(
+                output,
+                world,
+                settings.origin,
+                viewport,
+                (columns + 1, top),
+            )?);
+        }
+
+        Ok(write_status_below(output, &status, settings, top, rows)?)
+    }
+
+    fn update(mut self, message: Option<Event>, settings: &Settings) -> Result<State, Self::Error> {
+        if let Some(editor) = self.editor.take() {
+            match message {
+                Some(Event::Key(press)) => match editor.update(press.code) {
+                    Edit::Editing(editor) => self.editor = Some(editor),
+                    Edit::Closed(rule) => self.engine.set_rule(rule),
+                },
+                _ => self.editor = Some(editor),
+            }
+
+            return Ok(State::Simulate(self));
+        }
+
+        if let Some(Event::Key(press)) = message {
+            match press.code {
+                KeyCode::Char('e') => {
+                    let edge_behavior = self.engine.world().edge_behavior().next();
+                    self.engine.set_edge_behavior(edge_behavior);
+                }
+                KeyCode::Char('r') => {
+                    self.engine.reset();
+
+                    if matches!(self.pause, Some(Pause::MaxGenerations(_))) {
+                        self.pause = None;
+                    }
+
+                    return Ok(State::Simulate(self));
+                }
+                KeyCode::Char('h') => self.heatmap = !self.heatmap,
+                KeyCode::Char('t') => self.show_trace = !self.show_trace,
+                KeyCode::Char('T') => {
+                    self.trace.fill(false);
+                    self.record_trace();
+                }
+                KeyCode::Char('w') => self.seam = !self.seam,
+                KeyCode::Char('n') => self.minimap = !self.minimap,
+                KeyCode::Char('v') => self.render_mode = self.render_mode.next(),
+                KeyCode::Char('*') => {
+                    self.generations_per_frame =
+                        (self.generations_per_frame * 2).min(MAX_GENERATIONS_PER_FRAME);
+                }
+                KeyCode::Char('/') => {
+                    self.generations_per_frame = (self.generations_per_frame / 2).max(1)
+                }
+                KeyCode::Char('+' | '=') => self.set_zoom(zoom_in(self.zoom)),
+                KeyCode::Char('-') => self.set_zoom(zoom_out(self.zoom, self.engine.world())),
+                KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right => {
+                    self.scroll(press.code, settings)?;
+                }
+                KeyCode::Char('p') => {
+                    self.pause = match self.pause {
+                        Some(_) => None,
+                        None => Some(Pause::Manual),
+                    };
+                }
+                KeyCode::Char('s') => {
+                    self.saved = Some(save(
+                        self.engine.world(),
+                        self.engine.config().rule,
+                        &settings.save_path,
+                    ));
+                }
+                KeyCode::Char('u') => {
+                    self.editor = Some(RuleEditor::new(self.engine.config().rule));
+                    return Ok(State::Simulate(self));
+                }
+                _ => {}
+            }
+        }
+
+        // This is checked before stepping as well as after, so that resuming at the last generation stops again
+        // straight away rather than going one past it.
+        self.check_max_generations(settings);
+
+        if self.pause.is_some() {
+            return Ok(State::Simulate(self));
+        }
+
+        let mut simulated = 0;
+
+        for _ in 0..self.frame_generations() {
+            // Only pause when the world first settles, so that resuming afterwards doesn't immediately pause again.
+            let was_stable = self.engine.is_stable();
+            let was_over_budget = self.over_budget(settings);
+            self.engine.step();
+            self.record_trace();
+            simulated += 1;
+
+            if settings.pause_on_cycle && !was_stable {
+                self.pause = self
+                    .engine
+                    .cycle()
+                    .filter(|_| self.engine.is_stable())
+                    .map(Pause::Cycle);
+            }
+
+            // Like with cycles, this only pauses when the budget is first gone over, so that it can be resumed.
+            if !was_over_budget && self.over_budget(settings) {
+                self.pause = settings.max_cells.map(Pause::Budget);
+            }
+
+            if settings
+                .loop_after
+                .is_some_and(|generation| self.engine.generation() >= generation)
+            {
+                self.engine.reset();
+            }
+
+            self.check_max_generations(settings);
+
+            if self.pause.is_some() {
+                break;
+            }
+        }
+
+        self.record_frame(simulated);
+        Ok(State::Simulate(self))
+    }
+}
+
+impl Simulate {
+    /// Starts simulating `world` from generation 0.
+    fn new(world: World, rule: Rule, settings: &Settings) -> Self {
+        let config = EngineConfig {
+            rule,
+            border: settings.border,
+            ..EngineConfig::default()
+        };
+
+        let trace = world.iter().map(|cell| cell.state.alive()).collect();
+
+        Simulate {
+            engine: Box::new(SimEngine::new(world, config)),
+            heatmap: false,
+            seam: settings.seam,
+            pause: None,
+            editor: None,
+            saved: None,
+            scroll: (0, 0),
+            minimap: true,
+            render_mode: settings.render_mode,
+            zoom: 1,
+            generations_per_frame: settings.generations_per_frame,
+            trace,
+            show_trace: false,
+            frames: VecDeque::new(),
+            started: Instant::now(),
+        }
+    }
+
+    /// How long the current tick lasts, taking the ramp into account.
+    fn tick_length(&self, settings: &Settings) -> Duration {
+        match settings.ramp {
+            Some(ramp) => ramp.interval(self.engine.generation()),
+            None => settings.tick_length,
+        }
+    }
+
+    /// How many generations a second the simulation is meant to run at, or `None` if there's no tick length and it runs
+    /// as fast as it can.
+    fn target_rate(&self, settings: &Settings) -> Option<f64> {
+        let tick_length = self.tick_length(settings).as_secs_f64();
+        (tick_length > 0.0).then(|| self.frame_generations() as f64 / tick_length)
+    }
+
+    /// Notes that a frame just finished simulating `generations` generations, forgetting frames that are too old to be
+    /// measured any more.
+    fn record_frame(&mut self, generations: usize) {
+        let now = Instant::now();
+        self.frames.push_back((now, generations));
+
+        while self
+            .frames
+            .front()
+            .is_some_and(|&(time, _)| now - time > RATE_WINDOW)
+        {
+            self.frames.pop_front();
+        }
+    }
+
+    /// How many generations a second were really simulated over the last `RATE_WINDOW`, or since simulating started if
+    /// that was more recent.
+    fn achieved_rate(&self) -> f64 {
+        let window = self.started.elapsed().min(RATE_WINDOW).as_secs_f64();
+        let generations: usize = self
+            .frames
+            .iter()
+            .filter(|(time, _)| time.elapsed() <= RATE_WINDOW)
+            .map(|&(_, generations)| generations)
+            .sum();
+
+        if window > 0.0 {
+            generations as f64 / window
+        } else {
+            0.0
+        }
+    }
+
+    /// Adds the cells that are currently alive to the trace.
+    fn record_trace(&mut self) {
+        for (traced, cell) in self.trace.iter_mut().zip(self.engine.world().iter()) {
+            *traced |= cell.state.alive();
+        }
+    }
+
+    /// How many generations to simulate this frame. This is usually `generations_per_frame`, but it's cut down for
+    /// worlds that are too big to simulate that many generations of in one frame, though it's always at least 1.
+    fn frame_generations(&self) -> usize {
+        let world = self.engine.world();
+        let cells = (world.width() * world.height()).max(1);
+        self.generations_per_frame
+            .min(CELLS_PER_FRAME / cells)
+            .max(1)
+    }
+
+    /// Whether the world has more live cells than `Settings::max_cells` allows.
+    fn over_budget(&self, settings: &Settings) -> bool {
+        settings
+            .max_cells
+            .is_some_and(|budget| self.engine.population() > budget)
+    }
+
+    /// Stops the simulation if it's reached `Settings::max_generations`.
+    fn check_max_generations(&mut self, settings: &Settings) {
+        if let Some(max) = settings.max_generations {
+            if self.engine.generation() >= max {
+                self.pause = Some(Pause::MaxGenerations(max));
+            }
+        }
+    }
+
+    /// The terminal title to show while simulating.
+    fn title(&self) -> String {
+        format!(
+            "conway — gen {}, pop {}",
+            self.engine.generation(),
+            self.engine.population()
+        )
+    }
+
+    /// Lays out `shown` (the world as it is at the current zoom level) with the viewport scrolled to `self.scroll`, along
+    /// with the status text to show underneath it.
+    fn layout(
+        &self,
+        shown: &World,
+        settings: &Settings,
+    ) -> Result<(Layout, Vec<u8>), Box<dyn Error>> {
+        Layout::new(
+            shown,
+            None,
+            self.minimap,
+            |room| Viewport::new(shown, room, self.scroll),
+            |output, scrolling| {
+                self.write_status_lines(output, settings, scrolling)?;
+                Ok(write_quit(output, settings)?)
+            },
+        )
+    }
+
+    /// Zooms to a new zoom level, keeping the viewport over roughly the same part of the world.
+    fn set_zoom(&mut self, zoom: usize) {
+        let rescale = |position: usize| position * self.zoom / zoom;
+        self.scroll = (rescale(self.scroll.0), rescale(self.scroll.1));
+        self.zoom = zoom;
+    }
+
+    /// Scrolls the viewport by `SCROLL_STEP` characters in the direction of an arrow key. This starts from wherever the
+    /// viewport actually is, since `self.scroll` can be past the edge of the world if the terminal has been resized.
+    fn scroll(&mut self, key: KeyCode, settings: &Settings) -> Result<(), Box<dyn Error>> {
+        let shown = zoomed(self.engine.world(), self.zoom, settings.downsample);
+        let Viewport { left, top, .. } = self.layout(&shown, settings)?.0.viewport;
+
+        self.scroll = match key {
+            KeyCode::Up => (left, top.saturating_sub(SCROLL_STEP)),
+            KeyCode::Down => (left, top + SCROLL_STEP),
+            KeyCode::Left => (left.saturating_sub(SCROLL_STEP), top),
+            KeyCode::Right => (left + SCROLL_STEP, top),
+            _ => (left, top),
+        };
+
+        Ok(())
+    }
+
+    /// Writes the status text shown underneath the world, including the hints for scrolling if the world is too big
+    /// to fit on the screen.
+    fn write_status_lines(
+        &self,
+        output: &mut impl Write,
+        settings: &Settings,
+        scrolling: bool,
+    ) -> Result<(), Box<dyn Error>> {
+        let world = self.engine.world();
+
+        if let Some(editor) = &self.editor {
+            return Ok(editor.display(output)?);
+        }
+
+        status!(
+            output,
+            "Currently in {} mode",
+            "Simulation"
+                .bold()
+                .with(settings.theme.contrasting(Color::Magenta))
+        )?;
+
+        write_pattern_name(output, world)?;
+        write_saved(output, &self.saved, settings)?;
+
+        status!(
+            output,
+            "Currently at generation #{}",
+            self.engine.generation().to_string().bold()
+        )?;
+
+        let current = self.engine.world();
+        status!(
+            output,
+            "Population is {} ({} of cells are alive)",
+            current.population().to_string().bold(),
+            format!("{:.1}%", current.density() * 100.0).bold()
+        )?;
+
+        if self.pause.is_none() {
+            let target = match self.target_rate(settings) {
+                Some(rate) => format!("{:.1}", rate),
+                None => "as many as possible".to_string(),
+            };
+
+            status!(
+                output,
+                "Simulating {} generation(s) per second, aiming for {}",
+                format!("{:.1}", self.achieved_rate()).bold(),
+                target.bold()
+            )?;
+        }
+
+        if let Some(generation) = settings.loop_after {
+            status!(
+                output,
+                "Looping back to generation 0 after generation #{}",
+                generation.to_string().bold()
+            )?;
+        }
+
+        if let Some(changes) = self.engine.changes() {
+            status!(
+                output,
+                "{} cell(s) changed in the last generation",
+                changes.to_string().bold()
+            )?;
+        }
+
+        status!(
+            output,
+            "Simulating under rule {}",
+            self.engine.config().rule.to_string().bold()
+        )?;
+
+        status!(
+            output,
+            "Edges are currently {}",
+            world.edge_behavior().name().bold()
+        )?;
+
+        if let Some(cycle) = self.engine.cycle() {
+            status!(output, "Detected a {}", cycle.to_string().bold())?;
+        }
+
+        if let Some(period) = self.engine.cached_period() {
+            status!(
+                output,
+                "Cached oscillator, period {}",
+                period.to_string().bold()
+            )?;
+        }
+
+        let highlight = settings.theme.contrasting(Color::Yellow);
+
+        match self.pause {
+            Some(Pause::Manual) => status!(output, "{}", "Paused".bold().with(highlight))?,
+            Some(Pause::Cycle(cycle)) => status!(
+                output,
+                "{} after detecting a {}",
+                "Paused".bold().with(highlight),
+                cycle
+            )?,
+            Some(Pause::Budget(budget)) => status!(
+                output,
+                "{} ({} cells)",
+                "Memory budget reached".bold().red(),
+                budget
+            )?,
+            Some(Pause::MaxGenerations(max)) => status!(
+                output,
+                "{} max generation #{}",
+                "Reached".bold().with(highlight),
+                max.to_string().bold()
+            )?,
+            None => {}
+        }
+
+        status!(output, "{}: Pause or resume", "P".blue().bold())?;
+        status!(output, "{}: Change edge behavior", "E".blue().bold())?;
+        status!(output, "{}: Reset to generation 0", "R".blue().bold())?;
+        status!(output, "{}: Toggle heatmap", "H".blue().bold())?;
+        status!(
+            output,
+            "{}: Toggle trace of where cells have been alive, {}: Clear trace",
+            "T".blue().bold(),
+            "Shift + T".blue().bold()
+        )?;
+        status!(
+            output,
+            "{}: Simulate more or fewer generations per frame (currently {})",
+            "*/".blue().bold(),
+            self.frame_generations().to_string().bold()
+        )?;
+        status!(
+            output,
+            "{}: Change rendering (currently {})",
+            "V".blue().bold(),
+            self.render_mode.name()
+        )?;
+        write_zoom(output, self.zoom)?;
+
+        if scrolling {
+            status!(output, "{}: Scroll", "↑↓←→".blue().bold())?;
+            status!(output, "{}: Toggle minimap", "N".blue().bold())?;
+        }
+
+        if world.edge_behavior() != EdgeBehavior::Dead {
+            status!(output, "{}: Toggle wrap seam", "W".blue().bold())?;
+        }
+
+        status!(output, "{}: Edit rule", "U".blue().bold())?;
+        status!(output, "{}: Save pattern", "S".blue().bold())?;
+
+        Ok(())
+    }
+}
+
+impl RuleEditor {
+    pub fn new(rule: Rule) -> Self {
+        RuleEditor {
+            original: rule,
+            rule,
+            survival: false,
+        }
+    }
+
+    pub fn update(mut self, code: KeyCode) -> Edit {
+        match code {
+            KeyCode::Char(c) if c.is_ascii_digit() => {
+                let count = c.to_digit(10).unwrap() as usize;
+                let table: &mut [bool] = match &mut self.rule {
+                    Rule::BirthSurvival { survival, .. } if self.survival => survival,
+                    Rule::BirthSurvival { birth, .. } => birth,
+                    Rule::Voting { totals } => totals,
+                };
+
+                if let Some(enabled) = table.get_mut(count) {
+                    *enabled = !*enabled;
+                }
+            }
+            KeyCode::Tab => self.survival = !self.survival,
+            KeyCode::Enter => return Edit::Closed(self.rule),
+            KeyCode::Esc => return Edit::Closed(self.original),
+            _ => {}
+        }
+
+        Edit::Editing(self)
+    }
+
+    pub fn display(&self, output: &mut impl Write) -> io::Result<()> {
+        status!(output, "Editing rule {}", self.rule.to_string().bold())?;
+
+        match &self.rule {
+            Rule::BirthSurvival { birth, survival } => {
+                write_counts(output, "Birth", birth, !self.survival)?;
+                write_counts(output, "Survival", survival, self.survival)?;
+                status!(output, "{}: Toggle neighbor count", "0-8".blue().bold())?;
+                status!(
+                    output,
+                    "{}: Switch between birth and survival",
+                    "Tab".blue().bold()
+                )?;
+            }
+            Rule::Voting { totals } => {
+                write_counts(output, "Totals", totals, true)?;
+                status!(output, "{}: Toggle total", "0-9".blue().bold())?;
+            }
+        }
+
+        status!(output, "{}: Apply rule", "Enter".blue().bold())?;
+        status!(output, "{}: Cancel", "Esc".blue().bold())
+    }
+}
+
+/// Writes one row of the rule editor, with the enabled counts highlighted.
+fn write_counts(
+    output: &mut impl Write,
+    label: &str,
+    table: &[bool],
+    selected: bool,
+) -> io::Result<()> {
+    let counts: Vec<String> = table
+        .iter()
+        .enumerate()
+        .map(|(count, &enabled)| match enabled {
+            true => count.to_string().green().bold().to_string(),
+            false => count.to_string().dark_grey().to_string(),
+        })
+        .collect();
+
+    let marker = if selected { ">" } else { " " };
+    status!(output, "{} {}: {}", marker, label, counts.join(" "))
+}
+
+/// Saves `world` to `path` in RLE format along with the rule it's being simulated under, returning the absolute path it
+/// was saved to.
+fn save(world: &World, rule: Rule, path: &Path) -> Saved {
+    fs::write(path, world.clone().with_rule(rule).to_rle())
+        .and_then(|_| fs::canonicalize(path))
+        .map_err(|error| format!("couldn't save to {}: {}", path.display(), error))
+}
+
+/// Writes a status line describing the last save, if there's been one.
+fn write_saved(
+    output: &mut impl Write,
+    saved: &Option<Saved>,
+    settings: &Settings,
+) -> io::Result<()> {
+    match saved {
+        Some(Ok(path)) if settings.hyperlinks => status!(
+            output,
+            "Saved to \x1b]8;;file://{}\x1b\\{}\x1b]8;;\x1b\\",
+            path.display(),
+            path.display().to_string().bold()
+        ),
+        Some(Ok(path)) => status!(output, "Saved to {}", path.display().to_string().bold()),
+        Some(Err(error)) => status!(output, "{}", error.as_str().red()),
+        None => Ok(()),
+    }
+}
+
+/// Gridlines drawn between every `spacing` rows and columns of a world. These line up with the world's coordinates
+/// rather than the screen's, so there's always a line just before each multiple of `spacing`, whichever way up the
+/// world is displayed.
+#[derive(Debug, Clone, Copy)]
+struct Gridlines {
+    spacing: usize,
+    origin: Origin,
+    height: usize,
+}
+
+impl Gridlines {
+    fn before_column(&self, x: usize) -> bool {
+        x > 0 && x.is_multiple_of(self.spacing)
+    }
+
+    /// Whether there's a line between screen row `row` and the one above it.
+    fn before_row(&self, row: usize) -> bool {
+        if row == 0 {
+            return false;
+        }
+
+        let above = self.origin.row(row - 1, self.height);
+        above
+            .max(self.origin.row(row, self.height))
+            .is_multiple_of(self.spacing)
+    }
+}
+
+/// The part of a world that's on the screen. Like the cursor in `Draw`, this is in screen coordinates, so `top` is a
+/// row on the screen rather than a `y` coordinate in the world.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Viewport {
+    left: usize,
+    top: usize,
+    width: usize,
+    height: usize,
+}
+
+impl Viewport {
+    /// The biggest viewport that fits into `room` columns and rows of cells, with its top-left corner as close to
+    /// `corner` as it can be without going past the edges of the world.
+    fn new(world: &World, room: (usize, usize), corner: (usize, usize)) -> Self {
+        let width = world.width().min(room.0.max(1));
+        let height = world.height().min(room.1.max(1));
+
+        Viewport {
+            left: corner.0.min(world.width() - width),
+            top: corner.1.min(world.height() - height),
+            width,
+            height,
+        }
+    }
+
+    /// Like `Viewport::new`, but centered on `focus` rather than starting from a corner.
+    fn around(world: &World, room: (usize, usize), focus: (usize, usize)) -> Self {
+        let corner = (
+            focus.0.saturating_sub(room.0 / 2),
+            focus.1.saturating_sub(room.1 / 2),
+        );
+
+        Viewport::new(world, room, corner)
+    }
+
+    /// Whether the whole world fits inside the viewport, in which case there's nothing to scroll.
+    fn covers(&self, world: &World) -> bool {
+        self.width == world.width() && self.height == world.height()
+    }
+
+    fn contains(&self, (x, row): (usize, usize)) -> bool {
+        (self.left..self.left + self.width).contains(&x)
+            && (self.top..self.top + self.height).contains(&row)
+    }
+
+    /// Converts a position on the grid (with the row in screen order) into a position on the screen, relative to the
+    /// top-left corner of the viewport and making room for any gridlines.
+    fn screen(&self, gridlines: Option<Gridlines>, (x, row): (usize, usize)) -> (usize, usize) {
+        let (column, line) = (x - self.left, row - self.top);
+
+        match gridlines {
+            Some(gridlines) => (
+                column
+                    + (self.left + 1..=x)
+                        .filter(|&x| gridlines.before_column(x))
+                        .count(),
+                line + (self.top + 1..=row)
+                    .filter(|&row| gridlines.before_row(row))
+                    .count(),
+            ),
+            None => (column, line),
+        }
+    }
+
+    /// How many columns and rows of the screen the viewport takes up, including any gridlines.
+    fn screen_size(&self, gridlines: Option<Gridlines>) -> (usize, usize) {
+        if self.width == 0 || self.height == 0 {
+            return (0, 0);
+        }
+
+        let corner = (self.left + self.width - 1, self.top + self.height - 1);
+        let (x, y) = self.screen(gridlines, corner);
+        (x + 1, y + 1)
+    }
+}
+
+/// Shrinks a world down for showing at the given zoom level, where each character stands for a `zoom` by `zoom` block
+/// of cells. Nothing needs to be copied when the world isn't zoomed out.
+fn zoomed(world: &World, zoom: usize, mode: Downsample) -> Cow<'_, World> {
+    match zoom {
+        0 | 1 => Cow::Borrowed(world),
+        _ => Cow::Owned(world.downsampled(zoom, mode)),
+    }
+}
+
+/// The zoom level after zooming in by one step. Every cell is shown at a zoom level of 1.
+fn zoom_in(zoom: usize) -> usize {
+    zoom.saturating_sub(1).max(1)
+}
+
+/// The zoom level after zooming out by one step, stopping once the whole world fits into a single character.
+fn zoom_out(zoom: usize, world: &World) -> usize {
+    (zoom + 1).min(world.width().max(world.height()).max(1))
+}
+
+/// Where a world goes on the screen.
+#[derive(Debug, Clone, Copy)]
+struct Layout {
+    viewport: Viewport,
+    // Whether there's a minimap beside the viewport.
+    minimap: bool,
+}
+
+impl Layout {
+    /// Lays out a world along with the status text underneath it. `place` picks the viewport for a given amount of
+    /// room, and `write_status` writes the status text given whether the world is too big to fit. The status text is
+    /// written with the scrolling hints first, and written again without them if it turns out that the world fits.
+    fn new(
+        world: &World,
+        gridlines: Option<Gridlines>,
+        minimap: bool,
+        place: impl Fn((usize, usize)) -> Viewport,
+        write_status: impl Fn(&mut Vec<u8>, bool) -> Result<(), Box<dyn Error>>,
+    ) -> Result<(Self, Vec<u8>), Box<dyn Error>> {
+        let mut status = Vec::new();
+        write_status(&mut status, true)?;
+
+        let lines = status.iter().filter(|&&byte| byte == b'\n').count();
+        let layout = Layout::fit(world, lines, gridlines, minimap, place);
+
+        if layout.viewport.covers(world) {
+            status.clear();
+            write_status(&mut status, false)?;
+        }
+
+        Ok((layout, status))
+    }
+
+    /// Lays out a world above `status_lines` lines of status text. The minimap is only shown if it's turned on and the
+    /// world doesn't fit on the screen, and the room for it is taken away from the viewport.
+    fn fit(
+        world: &World,
+        status_lines: usize,
+        gridlines: Option<Gridlines>,
+        minimap: bool,
+        place: impl Fn((usize, usize)) -> Viewport,
+    ) -> Self {
+        let (columns, rows) = room(status_lines, gridlines);
+        let viewport = place((columns, rows));
+
+        if !minimap || viewport.covers(world) {
+            return Layout {
+                viewport,
+                minimap: false,
+            };
+        }
+
+        Layout {
+            viewport: place((columns.saturating_sub(MINIMAP_SIZE.0 + 1), rows)),
+            minimap: true,
+        }
+    }
+}
+
+/// How many columns and rows of cells fit on the screen, leaving `status_lines` rows free for the status text and making
+/// room for any gridlines. If the size of the terminal can't be found then everything is assumed to fit.
+fn room(status_lines: usize, gridlines: Option<Gridlines>) -> (usize, usize) {
+    let Ok((columns, rows)) = crossterm::terminal::size() else {
+        return (usize::MAX, usize::MAX);
+    };
+
+    // As well as the status text (which ends with the line about quitting) there's a blank line between it and the
+    // world, and one more line so that the newline at the very end doesn't scroll the screen.
+    let (columns, rows) = (
+        columns as usize,
+        (rows as usize).saturating_sub(status_lines + 2),
+    );
+
+    match gridlines {
+        Some(gridlines) => {
+            let fit = |length: usize| length - length / (gridlines.spacing + 1);
+            (fit(columns), fit(rows))
+        }
+        None => (columns, rows),
+    }
+}
+
+/// Writes the part of the world inside the viewport followed by a newline, flipping it vertically if the origin is at
+/// the bottom. `style` decides how the cell at each position in the world is drawn.
+fn write_world(
+    output: &mut impl Write,
+    world: &World,
+    origin: Origin,
+    viewport: Viewport,
+    gridlines: Option<Gridlines>,
+    style: impl Fn((usize, usize), Cell) -> StyledContent<char>,
+) -> io::Result<()> {
+    let columns = viewport.left..viewport.left + viewport.width;
+
+    // Lines along the edges of the viewport are left out, since there's either nothing or an off-screen cell on the
+    // other side of them.
+    let before_column = |x: usize| {
+        x > viewport.left && gridlines.is_some_and(|gridlines| gridlines.before_column(x))
+    };
+    let before_row = |row: usize| {
+        row > viewport.top && gridlines.is_some_and(|gridlines| gridlines.before_row(row))
+    };
+
+    for row in viewport.top..viewport.top + viewport.height {
+        let y = origin.row(row, world.height());
+
+        if before_row(row) {
+            for x in columns.clone() {
+                if before_column(x) {
+                    write!(output, "{}", '┼'.dark_grey())?;
+                }
+
+                write!(output, "{}", '─'.dark_grey())?;
+            }
+
+            execute!(output, Clear(ClearType::UntilNewLine))?;
+            writeln!(output)?;
+        }
+
+        for x in columns.clone() {
+            if before_column(x) {
+                write!(output, "{}", '│'.dark_grey())?;
+            }
+
+            write!(output, "{}", style((x, y), world[(x, y)]))?;
+        }
+
+        // Anything left over to the right of the row (such as a minimap that's since been turned off) is cleared away.
+        execute!(output, Clear(ClearType::UntilNewLine))?;
+        writeln!(output)?;
+    }
+
+    Ok(())
+}
+
+/// How cells are usually drawn, using the characters from `mode` with markers in yellow. When `seam` is set, the first
+/// column and row are dimmed if the world wraps around horizontally and vertically respectively, to show where the edges
+/// join up.
+fn cell_style(
+    world: &World,
+    seam: bool,
+    mode: RenderMode,
+) -> impl Fn((usize, usize), Cell) -> StyledContent<char> {
+    let edges = world.edge_behavior();
+    let seam_column = seam && edges.wraps_horizontally();
+    let seam_row = seam && edges.wraps_vertically();
+
+    move |(x, y), cell| {
+        let block = match cell {
+            Cell::Marker => cell.glyph(mode).dark_yellow(),
+            _ => cell.glyph(mode).stylize(),
+        };
+
+        if (seam_column && x == 0) || (seam_row && y == 0) {
+            block.dim()
+        } else {
+            block
+        }
+    }
+}
+
+/// Writes a downsampled copy of the whole world with its top-left corner at `(left, top)` on the screen, with the part
+/// that's inside the viewport highlighted. Returns how many rows of the screen it took up.
+fn write_minimap(
+    output: &mut impl Write,
+    world: &World,
+    origin: Origin,
+    viewport: Viewport,
+    (left, top): (usize, usize),
+) -> io::Result<usize> {
+    let scale = world
+        .width()
+        .div_ceil(MINIMAP_SIZE.0)
+        .max(world.height().div_ceil(MINIMAP_SIZE.1))
+        .max(1);
+    let map = world.downsampled(scale, Downsample::Any);
+
+    // The viewport's rows are in screen order, so they're turned back into a range of `y` coordinates first.
+    let first = origin.row(viewport.top, world.height());
+    let last = origin.row(viewport.top + viewport.height - 1, world.height());
+    let visible_columns = viewport.left / scale..=(viewport.left + viewport.width - 1) / scale;
+    let visible_rows = first.min(last) / scale..=first.max(last) / scale;
+
+    for row in 0..map.height() {
+        let y = origin.row(row, map.height());
+        execute!(output, move_to(left, top + row))?;
+
+        for x in 0..map.width() {
+            let block = map[(x, y)].block();
+
+            if visible_columns.contains(&x) && visible_rows.contains(&y) {
+                write!(output, "{}", block.on_dark_blue())?;
+            } else {
+                write!(output, "{}", block.dark_grey())?;
+            }
+        }
+    }
+
+    Ok(map.height())
+}
+
+/// Writes the status line about quitting, which comes at the end of the status text in every state.
+fn write_quit(output: &mut impl Write, settings: &Settings) -> io::Result<()> {
+    status!(
+        output,
+        "{}: Quit",
+        settings.quit_key.to_string().blue().bold()
+    )
+}
+
+/// Writes the status text at the top of the screen if that's where it goes, followed by a blank line. Returns the row of
+/// the screen that the world starts on, which is 0 when the status text goes underneath it instead.
+fn write_status_above(
+    output: &mut impl Write,
+    status: &[u8],
+    settings: &Settings,
+) -> io::Result<usize> {
+    if settings.status_position == StatusPosition::Bottom {
+        return Ok(0);
+    }
+
+    // The status text can be a different length from one frame to the next, so the whole screen is cleared first rather
+    // than leaving the ends of longer lines behind.
+    execute!(output, move_to(0, 0), Clear(ClearType::FromCursorDown))?;
+    write_status_text(output, status, terminal_columns())?;
+
+    Ok(status.iter().filter(|&&byte| byte == b'\n').count() + 1)
+}
+
+/// Clears everything below the world, which takes up `rows` rows of the screen starting at `top`, and writes the status
+/// text (after a blank line) if it goes underneath the world.
+fn write_status_below(
+    output: &mut impl Write,
+    status: &[u8],
+    settings: &Settings,
+    top: usize,
+    rows: usize,
+) -> io::Result<()> {
+    match settings.status_position {
+        StatusPosition::Bottom => {
+            execute!(
+                output,
+                move_to(0, rows + 1),
+                Clear(ClearType::FromCursorDown)
+            )?;
+            write_status_text(output, status, terminal_columns())
+        }
+        StatusPosition::Top => execute!(
+            output,
+            move_to(0, top + rows),
+            Clear(ClearType::FromCursorDown)
+        ),
+    }
+}
+
+/// Writes a status line with the keys for zooming, along with how far the world is zoomed out if it is at all.
+fn write_zoom(output: &mut impl Write, zoom: usize) -> io::Result<()> {
+    match zoom {
+        0 | 1 => status!(output, "{}: Zoom out or in", "-+".blue().bold()),
+        _ => status!(
+            output,
+            "{}: Zoom out or in (each character is {} cells)",
+            "-+".blue().bold(),
+            format!("{}x{}", zoom, zoom).bold()
+        ),
+    }
+}
+
+/// Writes a status line with the name of the pattern, if it has one.
+fn write_pattern_name(output: &mut impl Write, world: &World) -> io::Result<()> {
+    match &world.metadata().name {
+        Some(name) => status!(output, "Pattern: {}", name.as_str().bold()),
+        None => Ok(()),
+    }
+}
+
+/// Picks a color for a live neighbor count, going from cool colors for sparse areas to hot colors for dense ones.
+fn heat_color(live_neighbors: usize) -> Color {
+    match live_neighbors {
+        0 => Color::DarkBlue,
+        1 => Color::Blue,
+        2 => Color::Cyan,
+        3 => Color::Yellow,
+        4 => Color::DarkYellow,
+        _ => Color::Red,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyEvent;
+
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    /// The simplest options for running the app through `events`, writing to `output`.
+    fn options(
+        output: &mut Vec<u8>,
+        initial: Option<(World, StartMode)>,
+        events: Vec<Event>,
+    ) -> Options<'_, Vec<u8>> {
+        Options {
+            output,
+            tick_length: Duration::ZERO,
+            initial,
+            origin: Origin::TopLeft,
+            rule: Rule::conway(),
+            border: 0,
+            initial_width: 4,
+            initial_height: 4,
+            size_file: None,
+            max_cells: None,
+            ramp: None,
+            show_title: false,
+            screen_mode: ScreenMode::Inline,
+            loop_after: None,
+            max_generations: None,
+            generations_per_frame: 1,
+            blink_cursor: false,
+            grid_spacing: 10,
+            pause_on_cycle: false,
+            confirm_empty: false,
+            seam: false,
+            render_mode: RenderMode::Classic,
+            downsample: Downsample::Any,
+            status_position: StatusPosition::Bottom,
+            theme: Theme::Dark,
+            save_path: "pattern.rle".into(),
+            quit_key: QuitKey::default(),
+            hyperlinks: false,
+            events: Some(Box::new(events.into_iter())),
+        }
+    }
+
+    /// The settings that the simplest options give.
+    fn settings() -> Settings {
+        let mut output = Vec::new();
+        let app = App::new(options(&mut output, None, vec![]));
+        app.settings
+    }
+
+    /// Presses `code` in `Scale`, starting from a `width` by `height` grid, and returns the size it ends up as.
+    fn scale(width: usize, height: usize, code: KeyCode) -> (usize, usize) {
+        let scale = Scale { width, height };
+
+        match scale.update(Some(key(code)), &settings()).unwrap() {
+            State::Scale(scale) => (scale.width, scale.height),
+            _ => panic!("{:?} shouldn't leave Scale", code),
+        }
+    }
+
+    /// Starts drawing `world` with the simplest options, returning the `Draw` state along with its settings.
+    fn draw(world: World) -> (Draw, Settings) {
+        let mut output = Vec::new();
+        let app = App::new(options(&mut output, Some((world, StartMode::Draw)), vec![]));
+        let State::Draw(draw) = app.state else {
+            unreachable!()
+        };
+
+        (draw, app.settings)
+    }
+
+    /// Runs the app through `events` with the simplest options, after letting `configure` change them, and returns
+    /// everything it wrote.
+    fn run(
+        initial: Option<(World, StartMode)>,
+        events: Vec<Event>,
+        configure: impl FnOnce(&mut Options<Vec<u8>>),
+    ) -> String {
+        let mut output = Vec::new();
+        let mut options = options(&mut output, initial, events);
+
+        configure(&mut options);
+        App::new(options).run().unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn simulation_pauses_at_the_memory_budget() {
+        // An R-pentomino, which goes from 5 live cells to 6 in its first generation.
+        let pentomino = crate::format::Format::Rle
+            .parse("x = 16, y = 16\n6$7b2o$6b2o$7bo!")
+            .unwrap();
+        let events = vec![key(KeyCode::Char('x')); 5];
+        let output = run(Some((pentomino, StartMode::Simulate)), events, |options| {
+            options.max_cells = Some(5);
+        });
+
+        assert!(output.contains("Memory budget reached"));
+        assert!(output.contains("(5 cells)"));
+        assert!(output.contains("generation #\x1b[1m1\x1b[0m"));
+        assert!(!output.contains("generation #\x1b[1m2\x1b[0m"));
+    }
+
+    #[test]
+    fn initial_worlds_skip_scale() {
+        let soup = World::random(8, 8, 0.5, &mut crate::random::Rng::new(1));
+        let simulated = run(Some((soup.clone(), StartMode::Simulate)), vec![], |_| {});
+        let drawn = run(Some((soup, StartMode::Draw)), vec![], |_| {});
+
+        assert!(simulated.contains("Simulation") && !simulated.contains("Scale"));
+        assert!(drawn.contains("Drawing") && !drawn.contains("Scale"));
+    }
+
+    #[test]
+    fn simulation_stops_at_the_max_generation() {
+        let soup = World::random(8, 8, 0.5, &mut crate::random::Rng::new(1));
+        let events = vec![key(KeyCode::Char('x')); 10];
+        let output = run(Some((soup, StartMode::Simulate)), events, |options| {
+            options.max_generations = Some(5);
+        });
+
+        assert!(output.contains("max generation #\x1b[1m5\x1b[0m"));
+        assert!(output.contains("generation #\x1b[1m5\x1b[0m"));
+        assert!(!output.contains("generation #\x1b[1m6\x1b[0m"));
+    }
+
+    #[test]
+    fn empty_initial_worlds_start_in_scale() {
+        for (width, height) in [(0, 0), (0, 5), (5, 0)] {
+            for mode in [StartMode::Draw, StartMode::Simulate] {
+                let output = run(Some((World::new(width, height), mode)), vec![], |_| {});
+                assert!(output
+                    .contains("\x1b[1m4\x1b[0m cell(s) wide and \x1b[1m4\x1b[0m cell(s) high"));
+            }
+        }
+    }
+
+    #[test]
+    fn arrows_change_the_grid_size() {
+        assert_eq!(scale(3, 3, KeyCode::Up), (3, 2));
+        assert_eq!(scale(3, 3, KeyCode::Down), (3, 4));
+        assert_eq!(scale(3, 3, KeyCode::Left), (2, 3));
+        assert_eq!(scale(3, 3, KeyCode::Right), (4, 3));
+    }
+
+    #[test]
+    fn the_grid_is_always_at_least_1_by_1() {
+        assert_eq!(scale(1, 1, KeyCode::Up), (1, 1));
+        assert_eq!(scale(1, 1, KeyCode::Left), (1, 1));
+    }
+
+    #[test]
+    fn other_keys_leave_the_grid_size_alone() {
+        assert_eq!(scale(3, 3, KeyCode::Char('x')), (3, 3));
+        assert_eq!(scale(3, 3, KeyCode::Tab), (3, 3));
+    }
+
+    #[test]
+    fn scripted_events_drive_the_whole_flow() {
+        let events = vec![
+            key(KeyCode::Enter),
+            key(KeyCode::Char(' ')),
+            key(KeyCode::Char(' ')),
+            key(KeyCode::Enter),
+        ];
+        let output = run(None, events, |_| {});
+
+        let scale = output.find("Scale").unwrap();
+        let drawing = output.find("Drawing").unwrap();
+        let simulation = output.find("Simulation").unwrap();
+        assert!(scale < drawing && drawing < simulation);
+
+        // Flipping the same cell twice leaves the grid empty.
+        assert!(output[simulation..].contains("Population is \x1b[1m0\x1b[0m"));
+    }
+
+    #[test]
+    fn the_cursor_stays_inside_a_world_that_shrinks_under_it() {
+        let (mut draw, settings) = draw(World::new(8, 8));
+        (draw.x, draw.y) = (7, 7);
+        draw.world = World::new(3, 3);
+
+        draw.display(&mut Vec::new(), &settings).unwrap();
+
+        let State::Draw(draw) = draw
+            .update(Some(key(KeyCode::Char(' '))), &settings)
+            .unwrap()
+        else {
+            panic!("flipping a cell shouldn't leave Draw")
+        };
+
+        assert_eq!((draw.x, draw.y), (2, 2));
+        assert_eq!(draw.world.live_cells().collect::<Vec<_>>(), [(2, 2)]);
+    }
+
+    #[test]
+    fn status_lines_are_cut_off_at_the_terminal_width() {
+        let mut status = Vec::new();
+        status!(status, "{}: Pause or resume", "P".blue().bold()).unwrap();
+        status!(status, "Short").unwrap();
+
+        let mut output = Vec::new();
+        write_status_text(&mut output, &status, Some(8)).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        // The escape codes around the `P` don't take up any room.
+        let lines: Vec<_> = output.lines().map(text::visible_width).collect();
+        assert_eq!(lines, [8, 5]);
+        assert!(output.lines().next().unwrap().ends_with('…'));
+
+        let mut untouched = Vec::new();
+        write_status_text(&mut untouched, &status, None).unwrap();
+        assert_eq!(untouched, status);
+    }
+
+    #[test]
+    fn brushes_stop_growing_at_the_size_of_the_world() {
+        let (mut draw, settings) = draw(World::new(3, 2));
+
+        for _ in 0..10 {
+            let State::Draw(next) = draw
+                .update(Some(key(KeyCode::Char(']'))), &settings)
+                .unwrap()
+            else {
+                panic!("growing the brush shouldn't leave Draw")
+            };
+            draw = next;
+        }
+
+        assert_eq!(draw.brush, 3);
+
+        // Even from a corner, the biggest brush covers the whole world.
+        (draw.x, draw.y) = (2, 1);
+        draw.flip_brush(&settings);
+        assert!(draw.world.is_full());
+    }
+
+    #[test]
+    fn locks_are_rebuilt_when_the_world_changes_size() {
+        let (mut draw, settings) = draw(World::new(8, 8));
+        (draw.x, draw.y) = (7, 7);
+        draw.locks[7 * 8 + 7] = true;
+        draw.world = World::new(3, 3);
+
+        let State::Draw(draw) = draw
+            .update(Some(key(KeyCode::Char('l'))), &settings)
+            .unwrap()
+        else {
+            panic!("locking a cell shouldn't leave Draw")
+        };
+
+        assert_eq!(draw.locks.len(), 9);
+        assert!(draw.locked((2, 2)));
+        assert_eq!(draw.locks.iter().filter(|&&locked| locked).count(), 1);
+    }
+
+    #[cfg(feature = "clipboard")]
+    #[test]
+    fn hostile_clipboard_contents_are_shown_as_errors() {
+        let clipboards = [
+            "x = 4000000000, y = 4000000000\no!",
+            "x = 3, y = 3\n99999999999999999999999o!",
+            "x = 3, y = 2\n999999999999o!",
+            "x = 3, y = 3\n999999999999b$999999999999$o!",
+        ];
+
+        for text in clipboards {
+            let (mut draw, settings) = draw(World::new(8, 8));
+            draw.paste_text(Ok(text.to_string()), &settings);
+
+            let mut status = Vec::new();
+            draw.display(&mut status, &settings).unwrap();
+
+            let status = String::from_utf8(status).unwrap();
+            assert!(
+                status.contains("Couldn't paste the clipboard"),
+                "{:?}",
+                text
+            );
+            assert_eq!(draw.world.population(), 0, "{:?}", text);
+        }
     }
 }
@@ -0,0 +1,303 @@
+//! A compact binary format for worlds, meant for machines rather than people. It's much smaller than RLE for dense
+//! worlds, and quicker to read and write.
+//!
+//! The format is the magic bytes `CWAY`, a version byte, then the width and height as LEB128 varints. Those are followed
+//! by the cells in row-major order, packed 8 to a byte with the first cell in the lowest bit, and with any unused bits
+//! in the last byte left as 0.
+//!
+//! Share codes are the same bytes written in unpadded URL-safe base64, which fits on a single line and survives being
+//! pasted into chat, unlike multi-line RLE.
+
+use std::{
+    error::Error,
+    fmt::Display,
+    io::{self, Read, Write},
+};
+
+use crate::{cell::Cell, world::World};
+
+const MAGIC: &[u8; 4] = b"CWAY";
+const VERSION: u8 = 1;
+
+/// The characters used for share codes, which are the URL-safe base64 alphabet.
+const SHARE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// An error encountered while reading a world in the binary format.
+#[derive(Debug)]
+pub enum BinaryError {
+    /// The underlying reader failed.
+    Io(io::Error),
+    /// The input didn't start with the magic bytes, so it probably isn't in this format at all.
+    InvalidMagic,
+    /// The input was written by a version of the format that we don't know how to read.
+    UnsupportedVersion(u8),
+    /// The header was cut off, or its width or height didn't fit in a `usize`.
+    InvalidHeader,
+    /// The number of cells didn't match the width and height in the header.
+    SizeMismatch,
+    /// A share code had a character that isn't part of the base64 alphabet, or was a length that base64 can't be.
+    InvalidShareCode,
+}
+
+impl Display for BinaryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BinaryError::Io(error) => write!(f, "{}", error),
+            BinaryError::InvalidMagic => write!(f, "not a binary world"),
+            BinaryError::UnsupportedVersion(version) => {
+                write!(f, "unsupported binary world version {}", version)
+            }
+            BinaryError::InvalidHeader => write!(f, "invalid binary world header"),
+            BinaryError::SizeMismatch => {
+                write!(f, "the number of cells doesn't match the world's size")
+            }
+            BinaryError::InvalidShareCode => write!(f, "invalid share code"),
+        }
+    }
+}
+
+impl Error for BinaryError {}
+
+impl From<io::Error> for BinaryError {
+    fn from(error: io::Error) -> Self {
+        BinaryError::Io(error)
+    }
+}
+
+impl World {
+    /// Writes the world in the binary format. Only the size and cells are written, so the edge behavior, metadata, and
+    /// rule are lost. There's only one bit per cell, so markers are written as dead cells.
+    pub fn write_binary(&self, mut output: impl Write) -> io::Result<()> {
+        output.write_all(MAGIC)?;
+        output.write_all(&[VERSION])?;
+        write_varint(&mut output, self.width())?;
+        write_varint(&mut output, self.height())?;
+
+        let cells: Vec<Cell> = self.iter().map(|located| located.state).collect();
+        let packed: Vec<u8> = cells
+            .chunks(8)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .enumerate()
+                    .fold(0, |byte, (bit, cell)| byte | (cell.alive() as u8) << bit)
+            })
+            .collect();
+
+        output.write_all(&packed)
+    }
+
+    /// Reads a world in the binary format. The whole input is read, and anything after the cells is treated as an
+    /// error, since it means the header's size was wrong.
+    pub fn read_binary(mut input: impl Read) -> Result<World, BinaryError> {
+        let mut data = Vec::new();
+        input.read_to_end(&mut data)?;
+
+        let rest = data.strip_prefix(MAGIC).ok_or(BinaryError::InvalidMagic)?;
+        let (&version, mut rest) = rest.split_first().ok_or(BinaryError::InvalidHeader)?;
+
+        if version != VERSION {
+            return Err(BinaryError::UnsupportedVersion(version));
+        }
+
+        let width = read_varint(&mut rest)?;
+        let height = read_varint(&mut rest)?;
+        let length = width
+            .checked_mul(height)
+            .ok_or(BinaryError::InvalidHeader)?;
+
+        // Checking the length before allocating means that a corrupt header can't make us allocate a huge world.
+        if rest.len() != length.div_ceil(8) {
+            return Err(BinaryError::SizeMismatch);
+        }
+
+        let mut world = World::new(width, height);
+
+        for index in 0..length {
+            world[index] = Cell::from_u8((rest[index / 8] >> (index % 8)) & 1).unwrap();
+        }
+
+        // Unused bits are always written as 0, so any that aren't mean the cells were meant for a bigger world.
+        let unused = rest.last().map_or(0, |&last| match length % 8 {
+            0 => 0,
+            used => last >> used,
+        });
+
+        if unused != 0 {
+            return Err(BinaryError::SizeMismatch);
+        }
+
+        Ok(world)
+    }
+
+    /// Encodes the world as a share code, which is the binary format in base64. Like the binary format, this only keeps
+    /// the size and which cells are alive, but those come back exactly from `from_share_code`.
+    pub fn to_share_code(&self) -> String {
+        let mut data = Vec::new();
+        // Writing to a `Vec` can't fail.
+        self.write_binary(&mut data).unwrap();
+
+        data.chunks(3)
+            .flat_map(|chunk| {
+                let bits = chunk.iter().enumerate().fold(0u32, |bits, (index, &byte)| {
+                    bits | (byte as u32) << (16 - 8 * index)
+                });
+
+                // Each byte takes up 8 of the 6-bit characters' bits, rounded up.
+                (0..chunk.len() + 1).map(move |index| {
+                    SHARE_ALPHABET[(bits >> (18 - 6 * index)) as usize & 0x3f] as char
+                })
+            })
+            .collect()
+    }
+
+    /// Decodes a share code made by `to_share_code`. Whitespace around the code is ignored, so that it can be pasted in
+    /// with a trailing newline.
+    pub fn from_share_code(code: &str) -> Result<World, BinaryError> {
+        let code = code.trim().as_bytes();
+
+        // Every group of up to 4 characters makes up to 3 bytes, and a single character can't even make 1.
+        if code.len() % 4 == 1 {
+            return Err(BinaryError::InvalidShareCode);
+        }
+
+        let mut data = Vec::with_capacity(code.len() * 3 / 4);
+
+        for chunk in code.chunks(4) {
+            let mut bits = 0u32;
+
+            for (index, c) in chunk.iter().enumerate() {
+                let value = SHARE_ALPHABET
+                    .iter()
+                    .position(|letter| letter == c)
+                    .ok_or(BinaryError::InvalidShareCode)?;
+
+                bits |= (value as u32) << (18 - 6 * index);
+            }
+
+            data.extend((0..chunk.len() - 1).map(|index| (bits >> (16 - 8 * index)) as u8));
+        }
+
+        World::read_binary(data.as_slice())
+    }
+}
+
+fn write_varint(output: &mut impl Write, mut value: usize) -> io::Result<()> {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+
+        if value == 0 {
+            return output.write_all(&[byte]);
+        }
+
+        output.write_all(&[byte | 0x80])?;
+    }
+}
+
+/// Reads a varint from the start of `input`, moving `input` past it.
+fn read_varint(input: &mut &[u8]) -> Result<usize, BinaryError> {
+    let mut value: usize = 0;
+
+    for shift in (0..usize::BITS).step_by(7) {
+        let (&byte, rest) = input.split_first().ok_or(BinaryError::InvalidHeader)?;
+        *input = rest;
+
+        let bits = (byte & 0x7f) as usize;
+
+        // Catch any bits that would be shifted off the top, rather than silently dropping them.
+        if bits.leading_zeros() < shift {
+            return Err(BinaryError::InvalidHeader);
+        }
+
+        value |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(BinaryError::InvalidHeader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::Rng;
+
+    #[test]
+    fn share_codes_round_trip_exactly() {
+        for (seed, (width, height)) in [(0, 0), (1, 1), (8, 1), (7, 3), (30, 17)]
+            .into_iter()
+            .enumerate()
+        {
+            let world = World::random(width, height, 0.5, &mut Rng::new(seed as u64));
+            let code = world.to_share_code();
+
+            assert!(
+                code.bytes().all(|c| SHARE_ALPHABET.contains(&c)),
+                "{}",
+                code
+            );
+            assert_eq!(World::from_share_code(&code).unwrap(), world);
+            assert_eq!(
+                World::from_share_code(&format!("  {}\n", code)).unwrap(),
+                world
+            );
+        }
+    }
+
+    #[test]
+    fn binary_round_trips_exactly() {
+        let world = World::random(13, 5, 0.5, &mut Rng::new(3));
+        let mut data = Vec::new();
+        world.write_binary(&mut data).unwrap();
+
+        assert!(data.starts_with(MAGIC));
+        assert_eq!(World::read_binary(data.as_slice()).unwrap(), world);
+    }
+
+    #[test]
+    fn markers_are_shared_as_dead_cells() {
+        let mut world = World::new(3, 1);
+        world[(0, 0)] = Cell::Marker;
+        world[(2, 0)] = Cell::Alive;
+
+        let shared = World::from_share_code(&world.to_share_code()).unwrap();
+        assert_eq!(
+            shared.row(0),
+            Some(&[Cell::Dead, Cell::Dead, Cell::Alive][..])
+        );
+    }
+
+    #[test]
+    fn invalid_share_codes_are_errors() {
+        let code = World::random(9, 9, 0.5, &mut Rng::new(4)).to_share_code();
+
+        assert!(matches!(
+            World::from_share_code(&code[..code.len() - 2]),
+            Err(BinaryError::SizeMismatch)
+        ));
+        assert!(matches!(
+            World::from_share_code(&format!("{}AAAA", code)),
+            Err(BinaryError::SizeMismatch)
+        ));
+        assert!(matches!(
+            World::from_share_code("Q1dB!"),
+            Err(BinaryError::InvalidShareCode)
+        ));
+        assert!(matches!(
+            World::from_share_code("Q1dBW"),
+            Err(BinaryError::InvalidShareCode)
+        ));
+        assert!(matches!(
+            World::from_share_code("AAAA"),
+            Err(BinaryError::InvalidMagic)
+        ));
+        assert!(matches!(
+            World::from_share_code(""),
+            Err(BinaryError::InvalidMagic)
+        ));
+    }
+}
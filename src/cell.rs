@@ -1,18 +1,57 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Cells are ordered by their number, so dead cells come before live cells, and live cells come before markers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[repr(u8)]
 pub enum Cell {
-    Dead,
-    Alive,
+    #[default]
+    Dead = 0,
+    Alive = 1,
+    /// An annotation that doesn't take part in the simulation. Markers always count as dead neighbors, are never born,
+    /// and stay exactly where they are from one generation to the next. They're handy for leaving guide marks on a grid.
+    Marker = 2,
 }
 
 impl Cell {
+    /// Converts a byte into a cell, where `0` is dead, `1` is alive, and `2` is a marker. Any other byte gives `None`.
+    pub fn from_u8(byte: u8) -> Option<Self> {
+        match byte {
+            0 => Some(Cell::Dead),
+            1 => Some(Cell::Alive),
+            2 => Some(Cell::Marker),
+            _ => None,
+        }
+    }
+
+    /// Converts the cell into a byte, where `0` is dead, `1` is alive, and `2` is a marker.
+    pub fn to_u8(self) -> u8 {
+        self as u8
+    }
+
+    pub fn from_bool(alive: bool) -> Self {
+        if alive {
+            Cell::Alive
+        } else {
+            Cell::Dead
+        }
+    }
+
+    pub fn to_bool(self) -> bool {
+        self.alive()
+    }
+
     pub fn alive(&self) -> bool {
         matches!(self, Cell::Alive)
     }
 
+    pub fn marker(&self) -> bool {
+        matches!(self, Cell::Marker)
+    }
+
+    /// Swaps live cells for dead ones and vice versa. Markers aren't really either, so they're left alone.
     pub fn flipped(&self) -> Self {
         match self {
             Cell::Dead => Cell::Alive,
             Cell::Alive => Cell::Dead,
+            Cell::Marker => Cell::Marker,
         }
     }
 
@@ -20,17 +59,65 @@ impl Cell {
         *self = self.flipped();
     }
 
-    pub fn block(&self) -> char {
+    /// Converts a character into a cell, given the characters that represent live and dead cells. Any other character
+    /// gives `None`.
+    pub fn from_char(c: char, alive: char, dead: char) -> Option<Self> {
+        match c {
+            c if c == alive => Some(Cell::Alive),
+            c if c == dead => Some(Cell::Dead),
+            _ => None,
+        }
+    }
+
+    /// A lowercase name for the cell's state, for using in a sentence like "paints live cells".
+    pub fn name(&self) -> &'static str {
         match self {
-            Cell::Dead => '.',
-            Cell::Alive => '@',
+            Cell::Dead => "dead",
+            Cell::Alive => "live",
+            Cell::Marker => "marker",
+        }
+    }
+
+    pub fn block(&self) -> char {
+        self.glyph(RenderMode::Classic)
+    }
+
+    /// The character used to draw the cell in the given render mode.
+    pub fn glyph(&self, mode: RenderMode) -> char {
+        match (self, mode) {
+            (Cell::Dead, RenderMode::Classic) => '.',
+            (Cell::Dead, RenderMode::Minimal) => ' ',
+            (Cell::Alive, RenderMode::Classic) => '@',
+            (Cell::Alive, RenderMode::Minimal) => '█',
+            (Cell::Marker, _) => '+',
         }
     }
 }
 
-impl Default for Cell {
-    fn default() -> Self {
-        Cell::Dead
+/// A preset for which characters cells are drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RenderMode {
+    /// `@` for live cells and `.` for dead cells.
+    #[default]
+    Classic,
+    /// `█` for live cells and blank spaces for dead cells, which makes patterns stand out without the dotted
+    /// background. This is handy for screenshots.
+    Minimal,
+}
+
+impl RenderMode {
+    pub fn next(&self) -> Self {
+        match self {
+            RenderMode::Classic => RenderMode::Minimal,
+            RenderMode::Minimal => RenderMode::Classic,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderMode::Classic => "Classic",
+            RenderMode::Minimal => "Minimal",
+        }
     }
 }
 
@@ -39,7 +126,7 @@ pub struct LocatedCell {
     pub(crate) state: Cell,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Position {
     TopLeft,
     Top,
@@ -78,3 +165,31 @@ impl Position {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bytes_round_trip() {
+        for cell in [Cell::Dead, Cell::Alive, Cell::Marker] {
+            assert_eq!(Cell::from_u8(cell.to_u8()), Some(cell));
+            assert_eq!(cell.to_u8(), cell as u8);
+        }
+
+        assert_eq!(Cell::Dead.to_u8(), 0);
+        assert_eq!(Cell::Alive.to_u8(), 1);
+        assert_eq!(Cell::from_u8(3), None);
+        assert_eq!(Cell::from_u8(u8::MAX), None);
+    }
+
+    #[test]
+    fn bools_round_trip() {
+        assert_eq!(Cell::from_bool(true), Cell::Alive);
+        assert_eq!(Cell::from_bool(false), Cell::Dead);
+        assert!(Cell::Alive.to_bool());
+        assert!(!Cell::Dead.to_bool());
+        assert!(!Cell::Marker.to_bool());
+        assert_eq!(Cell::default(), Cell::Dead);
+    }
+}
@@ -0,0 +1,164 @@
+use std::path::PathBuf;
+
+use conway::{
+    app::{QuitKey, Theme},
+    format::Format,
+    rule::Rule,
+    world::EdgeBehavior,
+};
+
+pub const USAGE: &str = "\
+Usage: conway [options]
+
+Options:
+    --stdin            Read a pattern from standard input and start drawing with it
+    --input <path>     Read a pattern from a file and start drawing with it, which can be given more than once
+    --random           Start simulating a random soup straight away, rather than drawing something first
+    --size <size>      The size of the random soup, such as `80x40` (defaults to filling the terminal)
+    --seed <n>         The seed for the random soup, so that the same soup can be watched again
+    --tile <size>      Tile the input patterns in a grid this many slots across and down, such as `3x2`
+    --spacing <cells>  The number of dead cells between tiled patterns (defaults to 0)
+    --slot <size>      The size of each tile, such as `20x20` (defaults to the size of the biggest pattern)
+    --clip             Clip tiled patterns that are too big for their slot, rather than failing
+    --format <format>  The format of the pattern, either `rle` or `plaintext` (detected if not given)
+    --rule <rule>      The rule to simulate with, such as `B3/S23` or the voting rule `V56789`
+    --edges <edges>    How the edges of the world behave: `dead`, `wrap`, `wrap-horizontal`, or `wrap-vertical`
+    --save <path>      Where to save the pattern to when pressing `S` (defaults to `pattern.rle`)
+    --quit-key <key>   The key that quits, such as `q` or `ctrl+q` (defaults to `ctrl+c`)
+    --gens <n>         How many generations to simulate when running without the terminal UI
+    --dump-frames <dir>
+                       Write every generation up to `--gens` into a directory as RLE files, rather than starting the UI
+    --print            Print generation `--gens` (or generation 0) of the pattern and exit, rather than starting the UI
+    --check <path>     Check that a pattern file can be read and print a summary of it, rather than starting the UI
+    --grid-spacing <n> How many cells apart the gridlines are while drawing (defaults to 10, and 0 turns them off)
+    --minimal          Draw live cells as `█` and dead cells as blank spaces, rather than `@` and `.`
+    --zoom-majority    While zoomed out, only show blocks where most of the cells are alive, rather than any of them
+    --status-top       Show the status text above the grid rather than below it, so that tall grids don't push it away
+    --alternate-screen Draw on the terminal's alternate screen, so that its contents come back after quitting
+    --theme <theme>    Pick colors for a `dark` or `light` terminal background (detected from `COLORFGBG` if possible)
+    --hyperlinks       Show the saved file as a clickable link, for terminals that support OSC 8 hyperlinks
+    --max-gens <n>     Stop simulating once generation `n` is reached
+    --loop <n>         Go back to generation 0 after generation `n`, over and over
+    --gens-per-frame <n>
+                       Simulate `n` generations for every frame that's shown (defaults to 1, and can be up to 1024)
+    --pause-on-cycle   Pause the simulation once it settles into a still life or oscillator
+    --max-cells <n>    Pause the simulation once more than `n` cells are alive, so that runaway growth can be stopped
+    --allow-empty      Start simulating an empty grid straight away, rather than asking to press Enter again
+    --no-blink         Don't blink the cursor while drawing
+    --no-state         Don't remember the grid size between runs
+    --help             Show this message
+
+Environment variables:
+    CONWAY_TICK_MS     How long each generation lasts while simulating, in milliseconds (defaults to 100)";
+
+/// The command line arguments accepted by the binary.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub stdin: bool,
+    pub inputs: Vec<PathBuf>,
+    pub tile: Option<(usize, usize)>,
+    pub spacing: usize,
+    pub slot: Option<(usize, usize)>,
+    pub clip: bool,
+    pub format: Option<Format>,
+    pub rule: Option<Rule>,
+    pub edges: Option<EdgeBehavior>,
+    pub save: Option<PathBuf>,
+    pub hyperlinks: bool,
+    pub no_state: bool,
+    pub gens: Option<usize>,
+    pub dump_frames: Option<PathBuf>,
+    pub check: Option<PathBuf>,
+    pub grid_spacing: Option<usize>,
+    pub loop_after: Option<usize>,
+    pub max_generations: Option<usize>,
+    pub gens_per_frame: Option<usize>,
+    pub max_cells: Option<usize>,
+    pub quit_key: Option<QuitKey>,
+    pub theme: Option<Theme>,
+    pub print: bool,
+    pub minimal: bool,
+    pub zoom_majority: bool,
+    pub status_top: bool,
+    pub random: bool,
+    pub size: Option<(usize, usize)>,
+    pub seed: Option<u64>,
+    pub alternate_screen: bool,
+    pub pause_on_cycle: bool,
+    pub allow_empty: bool,
+    pub no_blink: bool,
+    pub help: bool,
+}
+
+impl Args {
+    /// Parses the given arguments, which should not include the name of the binary.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Args::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--stdin" => parsed.stdin = true,
+                "--input" => parsed.inputs.push(value(&mut args, &arg)?.into()),
+                "--tile" => parsed.tile = Some(size(&value(&mut args, &arg)?)?),
+                "--spacing" => parsed.spacing = number(&value(&mut args, &arg)?)?,
+                "--slot" => parsed.slot = Some(size(&value(&mut args, &arg)?)?),
+                "--clip" => parsed.clip = true,
+                "--format" => parsed.format = Some(value(&mut args, &arg)?.parse()?),
+                "--rule" => parsed.rule = Some(value(&mut args, &arg)?.parse()?),
+                "--edges" => parsed.edges = Some(value(&mut args, &arg)?.parse()?),
+                "--print" => parsed.print = true,
+                "--minimal" => parsed.minimal = true,
+                "--zoom-majority" => parsed.zoom_majority = true,
+                "--status-top" => parsed.status_top = true,
+                "--random" => parsed.random = true,
+                "--size" => parsed.size = Some(size(&value(&mut args, &arg)?)?),
+                "--seed" => parsed.seed = Some(number(&value(&mut args, &arg)?)? as u64),
+                "--alternate-screen" => parsed.alternate_screen = true,
+                "--save" => parsed.save = Some(value(&mut args, &arg)?.into()),
+                "--hyperlinks" => parsed.hyperlinks = true,
+                "--no-state" => parsed.no_state = true,
+                "--gens" => parsed.gens = Some(number(&value(&mut args, &arg)?)?),
+                "--grid-spacing" => parsed.grid_spacing = Some(number(&value(&mut args, &arg)?)?),
+                "--max-gens" => parsed.max_generations = Some(number(&value(&mut args, &arg)?)?),
+                "--loop" => parsed.loop_after = Some(number(&value(&mut args, &arg)?)?),
+                "--gens-per-frame" => {
+                    parsed.gens_per_frame = Some(number(&value(&mut args, &arg)?)?)
+                }
+                "--theme" => parsed.theme = Some(value(&mut args, &arg)?.parse()?),
+                "--quit-key" => parsed.quit_key = Some(value(&mut args, &arg)?.parse()?),
+                "--check" => parsed.check = Some(value(&mut args, &arg)?.into()),
+                "--dump-frames" => parsed.dump_frames = Some(value(&mut args, &arg)?.into()),
+                "--pause-on-cycle" => parsed.pause_on_cycle = true,
+                "--max-cells" => parsed.max_cells = Some(number(&value(&mut args, &arg)?)?),
+                "--allow-empty" => parsed.allow_empty = true,
+                "--no-blink" => parsed.no_blink = true,
+                "--help" | "-h" => parsed.help = true,
+                _ => return Err(format!("unknown argument `{}`", arg)),
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Takes the value that should follow `flag`, erroring if there isn't one.
+fn value(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("`{}` needs a value", flag))
+}
+
+fn number(value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid number `{}`", value))
+}
+
+/// Parses a size written as `<width>x<height>`, such as `3x2`.
+fn size(value: &str) -> Result<(usize, usize), String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| format!("invalid size `{}`, expected something like `3x2`", value))?;
+
+    Ok((number(width)?, number(height)?))
+}
@@ -0,0 +1,49 @@
+//! Reading text from the system clipboard, for pasting patterns into the terminal UI. This is only available with the
+//! `clipboard` feature.
+//!
+//! There's no way to get at the clipboard without pulling in a platform-specific dependency, so the usual command line
+//! tools are run instead. Whichever of them is installed and works first is used.
+
+use std::{
+    io,
+    process::{Command, Stdio},
+};
+
+/// The commands that can read the clipboard, along with their arguments, in the order they're tried.
+const COMMANDS: &[(&str, &[&str])] = &[
+    ("pbpaste", &[]),
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-out"]),
+    ("xsel", &["--clipboard", "--output"]),
+    ("powershell", &["-NoProfile", "-Command", "Get-Clipboard"]),
+];
+
+/// Reads the text on the system clipboard. This errors if none of the commands could read it, such as when none of them
+/// are installed, or when the clipboard holds something other than text.
+pub fn read_text() -> io::Result<String> {
+    for (program, args) in COMMANDS {
+        // A command that isn't installed fails to start, and one for a different display server (like `xclip` under
+        // Wayland without XWayland) fails to run, so either way the next one gets a turn.
+        let output = match Command::new(program)
+            .args(*args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            _ => continue,
+        };
+
+        return String::from_utf8(output.stdout).map_err(|_| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the clipboard doesn't hold any text",
+            )
+        });
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "none of pbpaste, wl-paste, xclip, xsel or powershell could read the clipboard",
+    ))
+}
@@ -0,0 +1,247 @@
+//! An async driver for running a simulation from inside an existing event loop, such as an async TUI. This is only
+//! available with the `async` feature.
+//!
+//! The driver doesn't depend on any particular runtime. Waiting for the next tick is done by a plain thread that wakes
+//! the task once the tick is due, so it works under tokio, async-std, or a hand-rolled executor alike. Each driver has
+//! one such thread for as long as it lives, which stops once the driver is dropped.
+//!
+//! Input isn't handled by the driver itself. Instead, race `Driver::tick` against your own source of input events (with
+//! `tokio::select!` or similar), and apply whatever the input asks for through `Driver::engine_mut` in between ticks:
+//!
+//! ```ignore
+//! loop {
+//!     tokio::select! {
+//!         world = driver.tick() => render(world),
+//!         Some(key) = keys.recv() => match key {
+//!             'r' => driver.engine_mut().reset(),
+//!             'e' => {
+//!                 let edges = driver.engine().world().edge_behavior().next();
+//!                 driver.engine_mut().set_edge_behavior(edges);
+//!             }
+//!             _ => {}
+//!         },
+//!     }
+//! }
+//! ```
+//!
+//! Dropping an unfinished `tick` future (which is what `select!` does when the other branch wins) is fine, and the next
+//! call to `tick` still finishes at the same time the dropped one would have.
+
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Condvar, Mutex},
+    task::{Context, Poll, Waker},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{engine::SimEngine, world::World};
+
+/// Steps a `SimEngine` once every `interval`, without blocking the thread it's awaited on.
+#[derive(Debug)]
+pub struct Driver {
+    engine: SimEngine,
+    interval: Duration,
+    // When the next tick is due. This only moves forward once a tick actually happens.
+    next_tick: Instant,
+    timer: Timer,
+}
+
+impl Driver {
+    /// Drives `engine`, with the first tick happening one `interval` from now.
+    pub fn new(engine: SimEngine, interval: Duration) -> Self {
+        Driver {
+            engine,
+            interval,
+            next_tick: Instant::now() + interval,
+            timer: Timer::new(),
+        }
+    }
+
+    /// Waits until the next tick is due, then advances the simulation by one generation and returns the new world.
+    pub async fn tick(&mut self) -> &World {
+        Sleep::until(self.next_tick, &self.timer).await;
+
+        // If we've fallen behind (say, because the caller was busy) then there's no point trying to catch up.
+        self.next_tick = (self.next_tick + self.interval).max(Instant::now());
+        self.engine.step();
+        self.engine.world()
+    }
+
+    /// Changes how long each tick lasts, starting from the tick after the one that's currently due.
+    pub fn set_interval(&mut self, interval: Duration) {
+        self.interval = interval;
+    }
+
+    /// Get a reference to the engine being driven.
+    pub fn engine(&self) -> &SimEngine {
+        &self.engine
+    }
+
+    /// Get a mutable reference to the engine being driven, for applying input in between ticks.
+    pub fn engine_mut(&mut self) -> &mut SimEngine {
+        &mut self.engine
+    }
+
+    /// Stops driving the engine, handing it back.
+    pub fn into_engine(self) -> SimEngine {
+        self.engine
+    }
+}
+
+/// A handle to a thread that wakes a task once a deadline has passed. The same thread is used for every deadline, and
+/// it's stopped when the handle is dropped.
+#[derive(Debug)]
+struct Timer {
+    shared: Arc<(Mutex<TimerState>, Condvar)>,
+}
+
+#[derive(Debug, Default)]
+struct TimerState {
+    // The deadline being waited for, along with the task to wake once it passes.
+    waiting: Option<(Instant, Waker)>,
+    stopped: bool,
+}
+
+impl Timer {
+    fn new() -> Self {
+        let shared = Arc::new((Mutex::new(TimerState::default()), Condvar::new()));
+        let thread_shared = Arc::clone(&shared);
+
+        thread::spawn(move || {
+            let (mutex, condvar) = &*thread_shared;
+            let mut state = mutex.lock().unwrap();
+
+            while !state.stopped {
+                let deadline = match &state.waiting {
+                    Some((deadline, _)) => *deadline,
+                    None => {
+                        state = condvar.wait(state).unwrap();
+                        continue;
+                    }
+                };
+
+                let now = Instant::now();
+
+                if now < deadline {
+                    // The deadline might change while we wait, so it's checked again either way.
+                    state = condvar.wait_timeout(state, deadline - now).unwrap().0;
+                    continue;
+                }
+
+                let (_, waker) = state.waiting.take().unwrap();
+
+                // Some executors poll the task straight away when it's woken, which would need the lock.
+                drop(state);
+                waker.wake();
+                state = mutex.lock().unwrap();
+            }
+        });
+
+        Timer { shared }
+    }
+
+    /// Makes sure the task behind `waker` is woken once `deadline` passes, instead of whatever was waiting before.
+    fn wake_at(&self, deadline: Instant, waker: &Waker) {
+        let (state, condvar) = &*self.shared;
+
+        state.lock().unwrap().waiting = Some((deadline, waker.clone()));
+        condvar.notify_one();
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        let (state, condvar) = &*self.shared;
+
+        state.lock().unwrap().stopped = true;
+        condvar.notify_one();
+    }
+}
+
+/// A future that finishes at `deadline`.
+struct Sleep<'a> {
+    deadline: Instant,
+    timer: &'a Timer,
+}
+
+impl<'a> Sleep<'a> {
+    fn until(deadline: Instant, timer: &'a Timer) -> Self {
+        Sleep { deadline, timer }
+    }
+}
+
+impl Future for Sleep<'_> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        if Instant::now() >= self.deadline {
+            return Poll::Ready(());
+        }
+
+        // The task might have moved to another executor thread since we were last polled, so the waker is replaced
+        // every time.
+        self.timer.wake_at(self.deadline, context.waker());
+        Poll::Pending
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::EngineConfig;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        task::Wake,
+        thread::Thread,
+    };
+
+    /// Wakes a thread that's blocked in `block_on`, counting how many times it's been woken.
+    struct Unpark {
+        thread: Thread,
+        wakes: AtomicUsize,
+    }
+
+    impl Wake for Unpark {
+        fn wake(self: Arc<Self>) {
+            self.wakes.fetch_add(1, Ordering::SeqCst);
+            self.thread.unpark();
+        }
+    }
+
+    /// Runs `future` to completion on the current thread, returning how many times the task was woken along the way.
+    fn block_on(future: impl Future) -> usize {
+        let unpark = Arc::new(Unpark {
+            thread: thread::current(),
+            wakes: AtomicUsize::new(0),
+        });
+        let waker = Waker::from(Arc::clone(&unpark));
+        let mut context = Context::from_waker(&waker);
+        let mut future = Box::pin(future);
+
+        while future.as_mut().poll(&mut context).is_pending() {
+            thread::park();
+        }
+
+        unpark.wakes.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn ticks_wait_for_the_interval() {
+        let interval = Duration::from_millis(10);
+        let engine = SimEngine::new(World::new(4, 4), EngineConfig::default());
+        let mut driver = Driver::new(engine, interval);
+        let start = Instant::now();
+
+        let wakes = block_on(async {
+            for _ in 0..3 {
+                driver.tick().await;
+            }
+        });
+
+        assert!(start.elapsed() >= interval * 3);
+        assert!(wakes >= 1);
+        assert_eq!(driver.engine().generation(), 3);
+    }
+}
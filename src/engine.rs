@@ -0,0 +1,413 @@
+use std::collections::VecDeque;
+
+use crate::{
+    analysis::{Cycle, CycleDetector},
+    rule::Rule,
+    world::{EdgeBehavior, World},
+};
+
+/// Configuration for a `SimEngine`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngineConfig {
+    /// The rule used to compute each generation.
+    pub rule: Rule,
+    /// How many previous generations are kept around for `step_back`.
+    pub history_depth: usize,
+    /// The width of a permanently dead frame around the edge of the world. Cells in the frame are killed after every
+    /// generation, which turns the world into a walled arena. This is 0 (no frame) by default.
+    pub border: usize,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        EngineConfig {
+            rule: Rule::conway(),
+            history_depth: 100,
+            border: 0,
+        }
+    }
+}
+
+/// Runs a simulation, keeping track of the generation count, recent history, and any cycle the world has settled
+/// into. This has nothing to do with displaying the world, so it can be used without a terminal.
+#[derive(Debug, Clone)]
+pub struct SimEngine {
+    world: World,
+    generation: usize,
+    // A snapshot of generation 0, so that we can always reset back to it.
+    initial: World,
+    history: VecDeque<World>,
+    detector: CycleDetector,
+    // The cycle completed by the current generation, if any. This is checked every generation, since a spaceship can
+    // stop being one when it hits a dead edge.
+    cycle: Option<Cycle>,
+    // How many cells changed state going from the previous generation to this one. This is `None` at generation 0, and
+    // after anything other than a tick has changed the world.
+    changes: Option<usize>,
+    // Every generation of the oscillator the world has settled into, once it's been found. Generations are then taken
+    // from here rather than being computed, since they'd come out the same anyway.
+    cache: Option<OscillatorCache>,
+    // A buffer for the next generation to be computed into, which is usually the oldest generation that just fell out
+    // of the history. This saves allocating a new world every generation.
+    spare: Option<World>,
+    config: EngineConfig,
+}
+
+/// How many generations are taken from an `OscillatorCache` between each check that it still matches what ticking
+/// would have given.
+const CACHE_CHECK_INTERVAL: usize = 64;
+
+/// The generations of an oscillator, in order, along with which one comes next.
+#[derive(Debug, Clone)]
+struct OscillatorCache {
+    generations: Vec<World>,
+    next: usize,
+    // How many generations have been taken since the cache was last checked.
+    unchecked: usize,
+}
+
+impl OscillatorCache {
+    /// Takes the next generation, along with whether it's time to check it against a real tick.
+    fn next(&mut self) -> (World, bool) {
+        let world = self.generations[self.next].clone();
+        self.next = (self.next + 1) % self.generations.len();
+        self.unchecked += 1;
+
+        let check = self.unchecked >= CACHE_CHECK_INTERVAL;
+
+        if check {
+            self.unchecked = 0;
+        }
+
+        (world, check)
+    }
+}
+
+impl SimEngine {
+    /// Starts simulating `world` from generation 0. The world's own rule is replaced with the one in `config`, so that
+    /// the world always has the rule it's actually being simulated under.
+    pub fn new(mut world: World, config: EngineConfig) -> Self {
+        world.kill_border(config.border);
+        world.set_rule(config.rule);
+
+        let mut engine = SimEngine {
+            initial: world.clone(),
+            world,
+            generation: 0,
+            history: VecDeque::with_capacity(config.history_depth),
+            detector: CycleDetector::default(),
+            cycle: None,
+            changes: None,
+            cache: None,
+            spare: None,
+            config,
+        };
+
+        engine.restart_detection();
+        engine
+    }
+
+    /// Advances the simulation by one generation. Once the world has settled into an oscillator, generations are
+    /// taken from a cache of its states rather than being computed. Every so often the cached generation is checked
+    /// against a real tick anyway, and the cache is thrown away if they ever disagree.
+    pub fn step(&mut self) {
+        let mut broke_cycle = false;
+
+        let next = match self.cache.as_mut().map(OscillatorCache::next) {
+            Some((cached, false)) => cached,
+            Some((cached, true)) => {
+                let ticked = self.tick();
+                broke_cycle = ticked != cached;
+                self.spare = Some(cached);
+                ticked
+            }
+            None => self.tick(),
+        };
+
+        let previous = std::mem::replace(&mut self.world, next);
+        self.changes = Some(self.world.changed_cells(&previous));
+
+        if self.history.len() == self.config.history_depth {
+            self.spare = self.history.pop_front();
+        }
+
+        if self.config.history_depth > 0 {
+            self.history.push_back(previous);
+        } else {
+            self.spare = Some(previous);
+        }
+
+        self.generation += 1;
+
+        // The cycle can't change while the cache is in use, since the same generations keep coming around. If it does
+        // anyway, the cycle detector hasn't been looking in the meantime, so it starts over.
+        if broke_cycle {
+            self.restart_detection();
+        } else if self.cache.is_none() {
+            self.cycle = self.detector.observe(&self.world);
+            self.cache = self.cycle.and_then(|cycle| self.oscillator_cache(cycle));
+        }
+    }
+
+    /// Computes the next generation, reusing the spare buffer if there is one.
+    fn tick(&mut self) -> World {
+        let (width, height) = (self.world.width(), self.world.height());
+        let mut next = self
+            .spare
+            .take()
+            .filter(|spare| spare.width() == width && spare.height() == height)
+            .unwrap_or_else(|| World::new(width, height));
+
+        self.world.tick_into_with(&self.config.rule, &mut next);
+        next.kill_border(self.config.border);
+        next
+    }
+
+    /// Goes back to the previous generation, returning `false` if there's no history left to go back to.
+    pub fn step_back(&mut self) -> bool {
+        match self.history.pop_back() {
+            Some(previous) => {
+                // The rule might have been changed since the previous generation.
+                self.world = previous;
+                self.world.set_rule(self.config.rule);
+                self.generation -= 1;
+                self.changes = None;
+                self.restart_detection();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Goes back to generation 0. The edge behavior and rule are runtime state, so they're kept rather than being reset
+    /// along with the cells.
+    pub fn reset(&mut self) {
+        let edge_behavior = self.world.edge_behavior();
+        self.world = self.initial.clone();
+        self.world.set_edge_behavior(edge_behavior);
+        self.world.set_rule(self.config.rule);
+        self.generation = 0;
+        self.changes = None;
+        self.history.clear();
+        self.restart_detection();
+    }
+
+    /// Changes the rule used to compute each generation, starting from the next generation.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.config.rule = rule;
+        self.world.set_rule(rule);
+        self.restart_detection();
+    }
+
+    /// Changes how the edges of the world behave, starting from the next generation.
+    pub fn set_edge_behavior(&mut self, edge_behavior: EdgeBehavior) {
+        self.world.set_edge_behavior(edge_behavior);
+        self.restart_detection();
+    }
+
+    /// How many cells are alive in the current generation.
+    pub fn population(&self) -> usize {
+        self.world.population()
+    }
+
+    /// Whether the world has settled into a still life or an oscillator. A moving spaceship doesn't count.
+    pub fn is_stable(&self) -> bool {
+        self.cycle.is_some_and(|cycle| !cycle.is_spaceship())
+    }
+
+    /// How many cells changed state in the last generation. A rate that drops to zero means the world has become a
+    /// still life, while a steady rate above zero usually means an oscillator or a spaceship. This is `None` until the
+    /// first generation after starting, resetting, or stepping back.
+    pub fn changes(&self) -> Option<usize> {
+        self.changes
+    }
+
+    /// The cycle completed by the current generation, if any.
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// The period of the oscillator whose generations are being taken from the cache, if there is one.
+    pub fn cached_period(&self) -> Option<usize> {
+        self.cache.as_ref().map(|cache| cache.generations.len())
+    }
+
+    /// Get a reference to the current generation's world.
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    /// Get the current generation number.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Get a reference to the engine's config.
+    pub fn config(&self) -> &EngineConfig {
+        &self.config
+    }
+
+    /// Forgets any cycle found so far, and starts looking again from the current generation. This is needed whenever
+    /// something other than a tick changes the world.
+    fn restart_detection(&mut self) {
+        self.detector.clear();
+        self.cache = None;
+        self.cycle = self.detector.observe(&self.world);
+    }
+
+    /// Builds a cache of an oscillator's generations out of the history, if the current generation completes `cycle`.
+    /// Spaceships can't be cached since they move, and nothing is cached unless the generation one period ago is still
+    /// in the history and matches the current one exactly, edges and all.
+    fn oscillator_cache(&self, cycle: Cycle) -> Option<OscillatorCache> {
+        let start = self.history.len().checked_sub(cycle.period)?;
+
+        if cycle.is_spaceship() || self.history[start] != self.world {
+            return None;
+        }
+
+        // The generation a period ago is the same as the current one, so it's left out in favor of the current one.
+        let generations = self
+            .history
+            .iter()
+            .skip(start + 1)
+            .chain(std::iter::once(&self.world))
+            .cloned()
+            .collect();
+
+        Some(OscillatorCache {
+            generations,
+            next: 0,
+            unchecked: 0,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    /// A 5x5 world holding a blinker, lying horizontally across the middle.
+    fn blinker() -> World {
+        Format::Rle.parse("x = 5, y = 5\n2$b3o!").unwrap()
+    }
+
+    /// An 8x8 world holding a glider in the top left corner, heading towards the bottom right.
+    fn glider() -> World {
+        Format::Rle.parse("x = 8, y = 8\nbo$2bo$3o!").unwrap()
+    }
+
+    #[test]
+    fn steps_match_ticking_the_world() {
+        let mut engine = SimEngine::new(glider(), EngineConfig::default());
+        let mut expected = glider();
+
+        for generation in 1..=4 {
+            engine.step();
+            expected = expected.tick();
+
+            assert_eq!(engine.generation(), generation);
+            assert_eq!(engine.world(), &expected);
+            assert_eq!(engine.population(), 5);
+        }
+    }
+
+    #[test]
+    fn stepping_back_retraces_the_steps() {
+        let mut engine = SimEngine::new(glider(), EngineConfig::default());
+        let mut seen = vec![engine.world().clone()];
+
+        for _ in 0..3 {
+            engine.step();
+            seen.push(engine.world().clone());
+        }
+
+        while let Some(expected) = seen.pop() {
+            assert_eq!(engine.world(), &expected);
+            assert_eq!(engine.generation(), seen.len());
+            assert_eq!(engine.step_back(), !seen.is_empty());
+        }
+
+        assert_eq!(engine.generation(), 0);
+    }
+
+    #[test]
+    fn stepping_back_stops_at_the_history_depth() {
+        let config = EngineConfig {
+            history_depth: 2,
+            ..EngineConfig::default()
+        };
+        let mut engine = SimEngine::new(glider(), config);
+
+        for _ in 0..5 {
+            engine.step();
+        }
+
+        assert!(engine.step_back());
+        assert!(engine.step_back());
+        assert!(!engine.step_back());
+        assert_eq!(engine.generation(), 3);
+        assert_eq!(engine.world(), &glider().tick_n(3, &Rule::conway()));
+    }
+
+    #[test]
+    fn a_glider_crashes_into_a_walled_edge() {
+        let config = EngineConfig {
+            border: 1,
+            ..EngineConfig::default()
+        };
+        // A glider just inside the wall, which would wrap around forever without it.
+        let mut glider = Format::Rle.parse("x = 12, y = 12\n$2bo$3bo$b3o!").unwrap();
+        glider.set_edge_behavior(EdgeBehavior::Wrap);
+
+        let mut engine = SimEngine::new(glider, config);
+
+        for _ in 0..60 {
+            engine.step();
+
+            let population = engine.population();
+            assert_eq!(
+                engine.world().region_population((1, 1), (10, 10)),
+                population
+            );
+        }
+
+        // What's left of it is a block, sitting against the wall.
+        assert!(engine.cycle().is_some_and(|cycle| cycle.is_still_life()));
+        assert_eq!(engine.population(), 4);
+    }
+
+    #[test]
+    fn oscillators_are_stable_but_spaceships_are_not() {
+        let mut blinker = SimEngine::new(blinker(), EngineConfig::default());
+        let mut glider = SimEngine::new(glider(), EngineConfig::default());
+
+        for _ in 0..4 {
+            blinker.step();
+            glider.step();
+        }
+
+        assert!(blinker.is_stable());
+        assert!(!glider.is_stable());
+    }
+
+    #[test]
+    fn a_stale_cache_is_thrown_away() {
+        let mut engine = SimEngine::new(blinker(), EngineConfig::default());
+
+        // A horizontal blinker doesn't stay horizontal, so this cache is wrong.
+        engine.cache = Some(OscillatorCache {
+            generations: vec![blinker()],
+            next: 0,
+            unchecked: 0,
+        });
+
+        for _ in 0..CACHE_CHECK_INTERVAL {
+            engine.step();
+        }
+
+        assert_eq!(engine.cached_period(), None);
+        assert_eq!(engine.world(), &blinker().tick());
+        assert_eq!(engine.population(), 3);
+    }
+}
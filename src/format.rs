@@ -0,0 +1,540 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+use crate::{
+    cell::Cell,
+    rule::Rule,
+    world::{Metadata, World, MAX_CELLS},
+};
+
+/// A text format that a pattern can be read from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// The run length encoded format used by most Life software, usually with a `.rle` extension.
+    Rle,
+    /// The plaintext format, usually with a `.cells` extension, where `O` is a live cell and `.` is a dead one.
+    Plaintext,
+}
+
+impl Format {
+    /// Guesses the format of `input`. Anything with an RLE header line is assumed to be RLE, and everything else is
+    /// assumed to be plaintext.
+    pub fn detect(input: &str) -> Self {
+        let is_rle = input
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .is_some_and(|line| line.starts_with('x') && line.contains('='));
+
+        if is_rle {
+            Format::Rle
+        } else {
+            Format::Plaintext
+        }
+    }
+
+    /// Parses `input` as a pattern in this format.
+    pub fn parse(&self, input: &str) -> Result<World, ParseError> {
+        if input.trim().is_empty() {
+            return Err(ParseError::Empty);
+        }
+
+        let world = match self {
+            Format::Rle => parse_rle(input),
+            Format::Plaintext => parse_plaintext(input),
+        }?;
+
+        if world.width() == 0 || world.height() == 0 {
+            return Err(ParseError::Empty);
+        }
+
+        Ok(world)
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Format::Rle => write!(f, "rle"),
+            Format::Plaintext => write!(f, "plaintext"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "rle" => Ok(Format::Rle),
+            "plaintext" | "cells" => Ok(Format::Plaintext),
+            _ => Err(format!("unknown pattern format `{}`", s)),
+        }
+    }
+}
+
+/// An error encountered while parsing a pattern.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// The input didn't contain any cells.
+    Empty,
+    /// The RLE header line was missing or malformed.
+    InvalidHeader(String),
+    /// A character that doesn't mean anything in the format was found.
+    InvalidCharacter(char),
+    /// The pattern contained live cells outside of the world, such as past the size given in an RLE header.
+    OutOfBounds,
+    /// A line in a coordinate list wasn't a pair of integers.
+    InvalidCoordinates(String),
+    /// The pattern's size would need more than `MAX_CELLS` cells.
+    TooBig { width: usize, height: usize },
+    /// A run in an RLE pattern was too long to count.
+    RunTooLong,
+}
+
+/// What to do with cells that fall outside of the world when placing a pattern into it.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Overflow {
+    /// Fail with `ParseError::OutOfBounds`.
+    #[default]
+    Error,
+    /// Silently leave the cells out.
+    Drop,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "the pattern is empty"),
+            ParseError::InvalidHeader(header) => write!(f, "invalid RLE header `{}`", header),
+            ParseError::InvalidCharacter(c) => write!(f, "unexpected character `{}`", c),
+            ParseError::OutOfBounds => write!(f, "the pattern doesn't fit in its declared size"),
+            ParseError::InvalidCoordinates(line) => write!(f, "invalid coordinates `{}`", line),
+            ParseError::TooBig { width, height } => write!(
+                f,
+                "the pattern is {}x{}, but patterns can have at most {} cells",
+                width, height, MAX_CELLS
+            ),
+            ParseError::RunTooLong => write!(f, "a run in the pattern is too long"),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl World {
+    /// Encodes the world in RLE format, including its name and comments if it has any. The world's rule goes in the
+    /// header.
+    pub fn to_rle(&self) -> String {
+        let mut output = String::new();
+        let metadata = self.metadata();
+
+        if let Some(name) = &metadata.name {
+            output.push_str(&format!("#N {}\n", name));
+        }
+
+        for comment in &metadata.comments {
+            output.push_str(&format!("#C {}\n", comment));
+        }
+
+        output.push_str(&format!(
+            "x = {}, y = {}, rule = {}\n",
+            self.width(),
+            self.height(),
+            self.rule()
+        ));
+
+        let mut tokens = Vec::new();
+        let mut pending_rows = 0;
+
+        for y in 0..self.height() {
+            let row = self.row(y).unwrap();
+
+            if y > 0 {
+                pending_rows += 1;
+            }
+
+            // Trailing dead cells don't need to be written, and neither do completely dead rows.
+            let Some(end) = row.iter().rposition(|cell| cell.alive()) else {
+                continue;
+            };
+
+            if pending_rows > 0 {
+                tokens.push(run(pending_rows, '$'));
+                pending_rows = 0;
+            }
+
+            // Markers can't be written in RLE, so they're treated as dead cells.
+            tokens.extend(
+                row[..=end]
+                    .chunk_by(|a, b| a.alive() == b.alive())
+                    .map(|cells| {
+                        let tag = if cells[0].alive() { 'o' } else { 'b' };
+                        run(cells.len(), tag)
+                    }),
+            );
+        }
+
+        tokens.push("!".to_string());
+
+        // Lines in RLE files shouldn't be longer than 70 characters, and runs can't be split across lines.
+        let mut line_length = 0;
+
+        for token in tokens {
+            if line_length > 0 && line_length + token.len() > 70 {
+                output.push('\n');
+                line_length = 0;
+            }
+
+            line_length += token.len();
+            output.push_str(&token);
+        }
+
+        output.push('\n');
+        output
+    }
+
+    /// Parses a pattern in Life 1.06 format, which is a list of `x y` coordinates with one live cell per line, into a
+    /// world of the given size. Coordinates can be negative, so `offset` is added to each of them before they're placed
+    /// into the world, and `overflow` decides what happens to cells that still don't fit. Lines starting with `#`
+    /// (including the `#Life 1.06` header) are skipped. Worlds with more than `MAX_CELLS` cells are errors, since the size
+    /// is often worked out from the coordinates in the file.
+    pub fn from_life106(
+        input: &str,
+        width: usize,
+        height: usize,
+        offset: (isize, isize),
+        overflow: Overflow,
+    ) -> Result<World, ParseError> {
+        let mut world =
+            World::try_new(width, height).map_err(|_| ParseError::TooBig { width, height })?;
+
+        for line in input.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid = || ParseError::InvalidCoordinates(line.to_string());
+            let mut coordinates = line.split_whitespace().map(str::parse::<isize>);
+
+            let (x, y) = match (coordinates.next(), coordinates.next(), coordinates.next()) {
+                (Some(Ok(x)), Some(Ok(y)), None) => {
+                    (x.checked_add(offset.0), y.checked_add(offset.1))
+                }
+                _ => return Err(invalid()),
+            };
+
+            // Coordinates that overflow once they're offset are so far away that they can't be inside the world.
+            let cell = x
+                .and_then(|x| usize::try_from(x).ok())
+                .zip(y.and_then(|y| usize::try_from(y).ok()))
+                .and_then(|position| world.get_mut(position));
+
+            match (cell, overflow) {
+                (Some(cell), _) => *cell = Cell::Alive,
+                (None, Overflow::Error) => return Err(ParseError::OutOfBounds),
+                (None, Overflow::Drop) => {}
+            }
+        }
+
+        Ok(world)
+    }
+
+    /// Lists the coordinates of every live cell as CSV, with an `x,y` header row.
+    pub fn to_csv(&self) -> String {
+        let mut output = String::from("x,y\n");
+
+        for (x, y) in self.live_cells() {
+            output.push_str(&format!("{},{}\n", x, y));
+        }
+
+        output
+    }
+}
+
+/// Formats a single RLE run, leaving out the count if it's 1.
+fn run(count: usize, tag: char) -> String {
+    if count == 1 {
+        tag.to_string()
+    } else {
+        format!("{}{}", count, tag)
+    }
+}
+
+fn parse_rle(input: &str) -> Result<World, ParseError> {
+    let mut metadata = Metadata::default();
+    let mut lines = input.lines().map(str::trim).filter(|line| !line.is_empty());
+
+    // Comment lines all come before the header.
+    let header = loop {
+        let line = lines.next().ok_or(ParseError::Empty)?;
+
+        match line.strip_prefix('#') {
+            Some(comment) => parse_rle_comment(comment, &mut metadata),
+            None => break line,
+        }
+    };
+
+    let (width, height, rule) = parse_rle_header(header)?;
+    let mut world =
+        World::try_new(width, height).map_err(|_| ParseError::TooBig { width, height })?;
+    *world.metadata_mut() = metadata;
+
+    if let Some(rule) = rule {
+        world.set_rule(rule);
+    }
+
+    let (mut x, mut y): (usize, usize) = (0, 0);
+    let mut count: Option<usize> = None;
+
+    'body: for line in lines {
+        for c in line.chars() {
+            match c {
+                '0'..='9' => {
+                    let digit = c.to_digit(10).unwrap() as usize;
+                    let run = count.unwrap_or(0).checked_mul(10);
+                    count = Some(
+                        run.and_then(|run| run.checked_add(digit))
+                            .ok_or(ParseError::RunTooLong)?,
+                    );
+                    continue;
+                }
+                'b' | 'o' => {
+                    let cell = Cell::from_char(c, 'o', 'b').unwrap();
+                    let end = x.saturating_add(count.unwrap_or(1));
+
+                    // Runs of dead cells are allowed to overhang the edge, since they don't change anything, so only the
+                    // part of the run that's inside the world is written.
+                    if cell.alive() && (end > world.width() || y >= world.height()) {
+                        return Err(ParseError::OutOfBounds);
+                    }
+
+                    for run_x in x..end.min(world.width()) {
+                        world.set((run_x, y), cell);
+                    }
+
+                    x = end;
+                }
+                '$' => {
+                    y = y.saturating_add(count.unwrap_or(1));
+                    x = 0;
+                }
+                '!' => break 'body,
+                c if c.is_whitespace() => {}
+                c => return Err(ParseError::InvalidCharacter(c)),
+            }
+
+            count = None;
+        }
+    }
+
+    Ok(world)
+}
+
+/// Parses a `#` line from an RLE file, without the `#`. Names and comments are kept, and anything else is ignored.
+fn parse_rle_comment(line: &str, metadata: &mut Metadata) {
+    let mut chars = line.chars();
+    let kind = chars.next();
+    let text = chars.as_str().trim().to_string();
+
+    match kind {
+        Some('N') => metadata.name = Some(text),
+        Some('C' | 'c') => metadata.comments.push(text),
+        _ => {}
+    }
+}
+
+/// Parses the `x = m, y = n` header line of an RLE file, returning the width and height along with the rule if there's
+/// a `rule` key. Rules written in a notation we don't understand are ignored rather than treated as errors, as are any
+/// other keys.
+fn parse_rle_header(header: &str) -> Result<(usize, usize, Option<Rule>), ParseError> {
+    let invalid = || ParseError::InvalidHeader(header.to_string());
+    let (mut width, mut height, mut rule) = (None, None, None);
+
+    for pair in header.split(',') {
+        let (key, value) = pair.split_once('=').ok_or_else(invalid)?;
+        let value = value.trim();
+
+        match key.trim() {
+            "x" => width = Some(value.parse().map_err(|_| invalid())?),
+            "y" => height = Some(value.parse().map_err(|_| invalid())?),
+            // Some programs add the shape of the grid after a colon, like `B3/S23:T64,64`, which we don't use.
+            "rule" => rule = value.split(':').next().and_then(|rule| rule.parse().ok()),
+            _ => {}
+        }
+    }
+
+    let (width, height) = width.zip(height).ok_or_else(invalid)?;
+    Ok((width, height, rule))
+}
+
+fn parse_plaintext(input: &str) -> Result<World, ParseError> {
+    let mut metadata = Metadata::default();
+    let mut rows = Vec::new();
+
+    for line in input.lines().map(str::trim_end) {
+        match line.strip_prefix('!') {
+            Some(comment) => match comment.strip_prefix("Name:") {
+                Some(name) => metadata.name = Some(name.trim().to_string()),
+                None => metadata.comments.push(comment.trim().to_string()),
+            },
+            None => rows.push(line),
+        }
+    }
+
+    // Trailing blank lines are almost always just the end of the file, rather than intentional rows of dead cells.
+    while rows.last().is_some_and(|row| row.is_empty()) {
+        rows.pop();
+    }
+
+    let width = rows
+        .iter()
+        .map(|row| row.chars().count())
+        .max()
+        .unwrap_or(0);
+    let height = rows.len();
+    let mut world =
+        World::try_new(width, height).map_err(|_| ParseError::TooBig { width, height })?;
+    *world.metadata_mut() = metadata;
+
+    for (y, row) in rows.iter().enumerate() {
+        for (x, c) in row.chars().enumerate() {
+            // Some files use `*` rather than `O` for live cells.
+            world[(x, y)] = Cell::from_char(c, 'O', '.')
+                .or_else(|| Cell::from_char(c, '*', '.'))
+                .ok_or(ParseError::InvalidCharacter(c))?;
+        }
+    }
+
+    Ok(world)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_dead_runs_stop_at_the_edge() {
+        let world = Format::Rle
+            .parse("x = 3, y = 2\n999999999999b$3o!")
+            .unwrap();
+
+        assert_eq!(world.population(), 3);
+        assert!(world.row(1).unwrap().iter().all(|cell| cell.alive()));
+    }
+
+    #[test]
+    fn overflowing_run_counts_are_errors() {
+        let result = Format::Rle.parse("x = 3, y = 3\n99999999999999999999999o!");
+        assert_eq!(result, Err(ParseError::RunTooLong));
+    }
+
+    #[test]
+    fn huge_headers_are_errors() {
+        let result = Format::Rle.parse("x = 4000000000, y = 4000000000\no!");
+        assert_eq!(
+            result,
+            Err(ParseError::TooBig {
+                width: 4000000000,
+                height: 4000000000
+            })
+        );
+
+        let overflowing = format!("x = {}, y = 2\no!", usize::MAX);
+        assert!(matches!(
+            Format::Rle.parse(&overflowing),
+            Err(ParseError::TooBig { .. })
+        ));
+    }
+
+    #[test]
+    fn offset_life106_coordinates_can_overflow() {
+        let input = format!("#Life 1.06\n{} 0\n0 0\n", isize::MAX);
+
+        assert_eq!(
+            World::from_life106(&input, 2, 2, (1, 1), Overflow::Error),
+            Err(ParseError::OutOfBounds)
+        );
+
+        let world = World::from_life106(&input, 2, 2, (1, 1), Overflow::Drop).unwrap();
+        assert_eq!(world.live_cells().collect::<Vec<_>>(), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn far_apart_life106_coordinates_are_errors() {
+        // Sizing the world to fit both of these cells would take billions of billions of cells.
+        let input = "#Life 1.06\n-2000000000 -2000000000\n2000000000 2000000000\n";
+        let (offset, size) = (2000000000, 4000000001);
+
+        assert_eq!(
+            World::from_life106(input, size, size, (offset, offset), Overflow::Drop),
+            Err(ParseError::TooBig {
+                width: size,
+                height: size
+            })
+        );
+
+        // A sensibly sized world only has room for one of them.
+        let world = World::from_life106(input, 4, 4, (offset, offset), Overflow::Drop).unwrap();
+        assert_eq!(world.live_cells().collect::<Vec<_>>(), vec![(0, 0)]);
+    }
+
+    #[test]
+    fn live_runs_past_the_edge_are_errors() {
+        let result = Format::Rle.parse("x = 3, y = 1\n4o!");
+        assert_eq!(result, Err(ParseError::OutOfBounds));
+    }
+
+    #[test]
+    fn rle_round_trips_with_its_metadata_and_rule() {
+        let input = "#N Glider\n#C The smallest spaceship.\n#C Found in 1970.\nx = 5, y = 4, rule = B36/S23\nbo$2bo$3o!\n";
+        let world = Format::Rle.parse(input).unwrap();
+
+        assert_eq!(world.metadata().name.as_deref(), Some("Glider"));
+        assert_eq!(
+            world.metadata().comments,
+            ["The smallest spaceship.", "Found in 1970."]
+        );
+        assert_eq!(world.rule(), Rule::new(&[3, 6], &[2, 3]));
+        assert_eq!(world.to_rle(), input);
+        assert_eq!(Format::Rle.parse(&world.to_rle()), Ok(world));
+    }
+
+    #[test]
+    fn long_rle_lines_round_trip() {
+        let mut world = World::new(200, 3);
+
+        for x in (0..200).step_by(3) {
+            world[(x, 1)] = Cell::Alive;
+        }
+
+        let rle = world.to_rle();
+        assert!(rle.lines().all(|line| line.len() <= 70));
+        assert_eq!(Format::Rle.parse(&rle), Ok(world));
+    }
+
+    #[test]
+    fn plaintext_round_trips_through_rle() {
+        let input =
+            "!Name: Blinker\n!Flips between horizontal and vertical.\n.....\n.OOO.\n.....\n";
+        let world = Format::Plaintext.parse(input).unwrap();
+
+        assert_eq!(world.metadata().name.as_deref(), Some("Blinker"));
+        assert_eq!(
+            world.metadata().comments,
+            ["Flips between horizontal and vertical."]
+        );
+        assert_eq!((world.width(), world.height()), (5, 3));
+        assert_eq!(
+            world.live_cells().collect::<Vec<_>>(),
+            [(1, 1), (2, 1), (3, 1)]
+        );
+        assert_eq!(Format::Rle.parse(&world.to_rle()), Ok(world));
+    }
+
+    #[test]
+    fn formats_are_detected() {
+        assert_eq!(Format::detect("x = 3, y = 1\n3o!"), Format::Rle);
+        assert_eq!(Format::detect("#N Blinker\nx = 3, y = 1\n3o!"), Format::Rle);
+        assert_eq!(Format::detect("!Name: Blinker\nOOO"), Format::Plaintext);
+    }
+}
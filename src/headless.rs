@@ -0,0 +1,111 @@
+//! Things the binary can do without taking over the terminal, for use from scripts.
+
+use std::{fs, path::Path};
+
+use crate::{
+    cell::RenderMode,
+    engine::{EngineConfig, SimEngine},
+    format::Format,
+    rule::Rule,
+    world::World,
+};
+
+/// Parses the pattern file at `path` without doing anything else with it, returning a one line summary of what was
+/// found. The summary is a list of `key=value` pairs, so that it's easy to pick apart from a script. The format is
+/// detected if it isn't given.
+pub fn check(path: &Path, format: Option<Format>) -> Result<String, String> {
+    let error = |error: &dyn std::error::Error| format!("{}: {}", path.display(), error);
+
+    let input = fs::read_to_string(path).map_err(|e| error(&e))?;
+    let format = format.unwrap_or_else(|| Format::detect(&input));
+    let world = format.parse(&input).map_err(|e| error(&e))?;
+
+    Ok(format!(
+        "path={} format={} width={} height={} population={}",
+        path.display(),
+        format,
+        world.width(),
+        world.height(),
+        world.population()
+    ))
+}
+
+/// Simulates `world` for `generations` generations under `rule`, and draws the last generation as text. Nothing is
+/// drawn for the generations in between.
+pub fn final_generation(world: World, generations: usize, rule: &Rule, mode: RenderMode) -> String {
+    let world = world.tick_n(generations, rule);
+
+    match mode {
+        RenderMode::Classic => world.to_string(),
+        RenderMode::Minimal => format!("{:#}", world),
+    }
+}
+
+/// Simulates `world` for `generations` generations, writing every generation (including generation 0) into `dir` as
+/// its own RLE file, named `gen0000.rle`, `gen0001.rle`, and so on. The directory is created if it doesn't exist yet.
+/// Each frame is written as soon as it's computed, so long runs don't need to be held in memory.
+pub fn dump_frames(
+    world: World,
+    config: EngineConfig,
+    generations: usize,
+    dir: &Path,
+) -> Result<(), String> {
+    let error = |path: &Path, error: std::io::Error| format!("{}: {}", path.display(), error);
+
+    fs::create_dir_all(dir).map_err(|e| error(dir, e))?;
+
+    // Every file name should have the same number of digits, so that they sort properly.
+    let digits = generations.to_string().len().max(4);
+    let mut engine = SimEngine::new(world, config);
+
+    loop {
+        let path = dir.join(format!("gen{:0digits$}.rle", engine.generation()));
+        fs::write(&path, engine.world().to_rle()).map_err(|e| error(&path, e))?;
+
+        if engine.generation() == generations {
+            return Ok(());
+        }
+
+        engine.step();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A 5x5 world holding a blinker, lying horizontally across the middle.
+    fn blinker() -> World {
+        Format::Rle.parse("x = 5, y = 5\n2$b3o!").unwrap()
+    }
+
+    #[test]
+    fn only_the_final_generation_is_drawn() {
+        let vertical = ".....\n..@..\n..@..\n..@..\n.....";
+
+        assert_eq!(
+            final_generation(blinker(), 1, &Rule::conway(), RenderMode::Classic),
+            vertical
+        );
+        assert_eq!(
+            final_generation(blinker(), 3, &Rule::conway(), RenderMode::Classic),
+            vertical
+        );
+        assert_eq!(
+            final_generation(blinker(), 0, &Rule::conway(), RenderMode::Minimal),
+            "     \n     \n ███ \n     \n     "
+        );
+    }
+
+    #[test]
+    fn the_final_generation_follows_the_rule() {
+        // Under B3/S nothing survives, so the blinker dies and the two cells born beside it die a generation later.
+        let rule = Rule::new(&[3], &[]);
+        let dead = ".....\n.....\n.....\n.....\n.....";
+
+        assert_eq!(
+            final_generation(blinker(), 2, &rule, RenderMode::Classic),
+            dead
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Experimental support for Life on a hexagonal grid, where every cell has six neighbors rather than eight.
+//!
+//! Cells are stored in rows like a square world, using "odd-r" offset coordinates: every odd row is pushed half a cell to
+//! the right, so the cell at `(x, y)` touches two cells in each of the rows above and below it. On even rows those are
+//! at `x - 1` and `x`, and on odd rows they're at `x` and `x + 1`.
+//!
+//! This is entirely separate from `World`, which is always a square grid.
+
+use std::{
+    fmt::{Display, Write},
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    cell::Cell,
+    rule::{self, Rule},
+};
+
+/// The neighbors of a cell on an even row, as offsets from the cell.
+const EVEN_OFFSETS: [(isize, isize); 6] = [(-1, 0), (1, 0), (-1, -1), (0, -1), (-1, 1), (0, 1)];
+
+/// The neighbors of a cell on an odd row, as offsets from the cell.
+const ODD_OFFSETS: [(isize, isize); 6] = [(-1, 0), (1, 0), (0, -1), (1, -1), (0, 1), (1, 1)];
+
+/// A hexagonal grid of cells. Everything beyond the edges is considered dead.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct HexWorld {
+    width: usize,
+    height: usize,
+    cells: Box<[Cell]>,
+}
+
+impl HexWorld {
+    /// Constructs a new `HexWorld` with the specified width and height, where every cell is dead.
+    pub fn new(width: usize, height: usize) -> Self {
+        HexWorld {
+            width,
+            height,
+            cells: vec![Cell::Dead; width * height].into_boxed_slice(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (x, y): (usize, usize)) -> Option<Cell> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    /// The number of live cells in the world.
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.alive()).count()
+    }
+
+    /// Counts how many of the six neighbors of a cell are alive. Markers count as dead neighbors.
+    pub fn live_neighbors(&self, (x, y): (usize, usize)) -> usize {
+        let offsets = if y % 2 == 0 {
+            EVEN_OFFSETS
+        } else {
+            ODD_OFFSETS
+        };
+
+        offsets
+            .iter()
+            .filter_map(|&(dx, dy)| {
+                let x = x.checked_add_signed(dx)?;
+                let y = y.checked_add_signed(dy)?;
+                self.get((x, y))
+            })
+            .filter(|cell| cell.alive())
+            .count()
+    }
+
+    /// Computes the next generation of the world under the given rule. Neighbor counts never go above 6, so anything in
+    /// the rule about higher counts has no effect. Markers stay where they are, just like in a square world.
+    pub fn tick(&self, rule: &Rule) -> Self {
+        let mut next = HexWorld::new(self.width, self.height);
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let cell = self[(x, y)];
+
+                next[(x, y)] = match cell {
+                    Cell::Marker => Cell::Marker,
+                    _ => {
+                        let neighbors = self.live_neighbors((x, y));
+                        Cell::from_bool(rule::next_state(cell.alive(), neighbors, rule))
+                    }
+                };
+            }
+        }
+
+        next
+    }
+}
+
+impl Index<(usize, usize)> for HexWorld {
+    type Output = Cell;
+
+    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
+        assert!(x < self.width && y < self.height, "position out of bounds");
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl IndexMut<(usize, usize)> for HexWorld {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
+        assert!(x < self.width && y < self.height, "position out of bounds");
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+/// Draws the world with a space between each cell, and with every odd row pushed one character to the right, so that
+/// each cell sits between the two cells it touches in the rows above and below it.
+impl Display for HexWorld {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            if y % 2 == 1 {
+                f.write_char(' ')?;
+            }
+
+            for x in 0..self.width {
+                if x > 0 {
+                    f.write_char(' ')?;
+                }
+
+                f.write_char(self[(x, y)].block())?;
+            }
+
+            // We don't want to leave a trailing newline, just like the `Display` impl for `World`.
+            if y + 1 < self.height {
+                f.write_char('\n')?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Rule {
+    /// A common rule for Life on a hexagonal grid, B2/S34. Random soups settle into plenty of oscillators under it. The
+    /// simplest is a pair of side by side live cells, which swaps back and forth with the pair of cells above and below
+    /// it every generation.
+    pub fn hex() -> Self {
+        Rule::new(&[2], &[3, 4])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A hex world with the given cells alive.
+    fn hex_world(width: usize, height: usize, live: &[(usize, usize)]) -> HexWorld {
+        let mut world = HexWorld::new(width, height);
+
+        for &position in live {
+            world[position] = Cell::Alive;
+        }
+
+        world
+    }
+
+    #[test]
+    fn neighbors_depend_on_the_row() {
+        // On an even row, the cells above and below are at `x - 1` and `x`.
+        let even = hex_world(5, 5, &[(1, 1), (2, 1), (3, 1), (1, 3), (2, 3), (3, 3)]);
+        assert_eq!(even.live_neighbors((2, 2)), 4);
+
+        // On an odd row, they're at `x` and `x + 1`.
+        let odd = hex_world(5, 5, &[(1, 2), (2, 2), (3, 2), (1, 4), (2, 4), (3, 4)]);
+        assert_eq!(odd.live_neighbors((2, 3)), 4);
+
+        let sides = hex_world(5, 5, &[(1, 2), (3, 2)]);
+        assert_eq!(sides.live_neighbors((2, 2)), 2);
+        assert_eq!(hex_world(1, 1, &[(0, 0)]).live_neighbors((0, 0)), 0);
+    }
+
+    #[test]
+    fn a_pair_of_cells_oscillates() {
+        let pair = hex_world(6, 5, &[(2, 2), (3, 2)]);
+        let flipped = pair.tick(&Rule::hex());
+
+        assert_eq!(flipped, hex_world(6, 5, &[(2, 1), (2, 3)]));
+        assert_eq!(flipped.tick(&Rule::hex()), pair);
+    }
+
+    #[test]
+    fn odd_rows_are_drawn_shifted() {
+        let world = hex_world(3, 2, &[(0, 0), (2, 1)]);
+        assert_eq!(world.to_string(), "@ . .\n . . @");
+    }
+}
@@ -0,0 +1,38 @@
+//! Conway's Game of Life, along with a terminal UI for drawing and simulating patterns.
+//!
+//! The types most embedders need are re-exported here: `World` and `Cell` for building and simulating worlds, `Rule`
+//! for choosing how they evolve, `Format` for reading patterns (with `World::to_rle` and friends for writing them), and
+//! `App` with `Options` for running the terminal UI against any writer. Transformations such as `World::inverted`,
+//! `World::trimmed` and `World::subgrid` are methods on `World` itself.
+//!
+//! Everything under a `pub mod` is part of the public API. The helpers for measuring text with escape sequences in it
+//! are private, since they only exist for the UI's status lines, and command line parsing lives in the binary.
+
+pub mod analysis;
+pub mod app;
+pub mod binary;
+pub mod cell;
+#[cfg(feature = "clipboard")]
+pub mod clipboard;
+#[cfg(feature = "async")]
+pub mod driver;
+pub mod engine;
+pub mod format;
+pub mod headless;
+pub mod hex;
+pub mod library;
+pub mod margolus;
+pub mod random;
+pub mod rule;
+#[cfg(feature = "soup")]
+pub mod soup;
+pub mod state;
+mod text;
+pub mod tile;
+pub mod world;
+
+pub use app::{App, Options};
+pub use cell::{Cell, Position};
+pub use format::{Format, ParseError};
+pub use rule::Rule;
+pub use world::World;
@@ -0,0 +1,78 @@
+//! A small library of well known patterns, for stamping into a world while drawing.
+
+use crate::{format::Format, world::World};
+
+/// A named pattern, stored as RLE.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pattern {
+    pub name: &'static str,
+    rle: &'static str,
+}
+
+impl Pattern {
+    /// Parses the pattern into a world that's just big enough to hold it, named after the pattern.
+    pub fn world(&self) -> World {
+        let mut world = Format::Rle
+            .parse(self.rle)
+            .expect("built-in patterns should always be valid");
+
+        world.metadata_mut().name = Some(self.name.to_string());
+        world
+    }
+}
+
+/// Every pattern in the library, roughly from smallest to largest.
+pub const PATTERNS: &[Pattern] = &[
+    Pattern {
+        name: "Block",
+        rle: "x = 2, y = 2\n2o$2o!",
+    },
+    Pattern {
+        name: "Beehive",
+        rle: "x = 4, y = 3\nb2o$o2bo$b2o!",
+    },
+    Pattern {
+        name: "Blinker",
+        rle: "x = 3, y = 1\n3o!",
+    },
+    Pattern {
+        name: "Toad",
+        rle: "x = 4, y = 2\nb3o$3o!",
+    },
+    Pattern {
+        name: "Beacon",
+        rle: "x = 4, y = 4\n2o$2o$2b2o$2b2o!",
+    },
+    Pattern {
+        name: "Glider",
+        rle: "x = 3, y = 3\nbo$2bo$3o!",
+    },
+    Pattern {
+        name: "Lightweight spaceship",
+        rle: "x = 5, y = 4\nbo2bo$o$o3bo$4o!",
+    },
+    Pattern {
+        name: "R-pentomino",
+        rle: "x = 3, y = 3\nb2o$2o$bo!",
+    },
+    Pattern {
+        name: "Diehard",
+        rle: "x = 8, y = 3\n6bo$2o$bo3b3o!",
+    },
+    Pattern {
+        name: "Acorn",
+        rle: "x = 7, y = 3\nbo$3bo$2o2b3o!",
+    },
+    Pattern {
+        name: "Gosper glider gun",
+        rle: "x = 36, y = 9\n24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4bobo$10bo5bo7bo$\
+              11bo3bo$12b2o!",
+    },
+];
+
+/// Looks up a pattern by name, ignoring case.
+pub fn find(name: &str) -> Option<&'static Pattern> {
+    PATTERNS
+        .iter()
+        .find(|pattern| pattern.name.eq_ignore_ascii_case(name))
+}
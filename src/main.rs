@@ -1,17 +1,295 @@
-use app::{App, Options};
-use std::{error::Error, time::Duration};
+use cli::Args;
+use conway::{
+    app::{App, Options, Origin, ScreenMode, StartMode, StatusPosition, Theme},
+    cell::RenderMode,
+    engine::EngineConfig,
+    format::{Format, Overflow},
+    headless,
+    random::Rng,
+    rule::Rule,
+    state,
+    tile::Tiling,
+    world::{Downsample, World, WorldError, MAX_CELLS},
+};
+use std::{
+    error::Error,
+    fs,
+    io::{IsTerminal, Read},
+    time::{Duration, SystemTime},
+};
 
-pub mod app;
-pub mod cell;
-pub mod world;
+mod cli;
+
+/// How long each frame lasts when `CONWAY_TICK_MS` isn't set.
+const DEFAULT_TICK_LENGTH: Duration = Duration::from_millis(100);
+
+/// How likely each cell of a `--random` soup is to start out alive.
+const RANDOM_DENSITY: f64 = 0.35;
+
+/// How many rows of the terminal are left for the status text when a `--random` soup is sized to fit the terminal.
+const STATUS_ROWS: usize = 24;
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        std::process::exit(1);
+    }
+}
+
+/// Makes the soup for `--random`, which fills the terminal (apart from room for the status text) unless `--size` is
+/// given. Without `--seed`, the seed comes from the current time so that every run is different. This errors rather
+/// than allocating if the soup would have more than `MAX_CELLS` cells.
+fn random_soup(args: &Args) -> Result<World, WorldError> {
+    let (width, height) = args.size.unwrap_or_else(|| {
+        crossterm::terminal::size().map_or((64, 32), |(columns, rows)| {
+            (
+                columns as usize,
+                (rows as usize).saturating_sub(STATUS_ROWS).max(8),
+            )
+        })
+    });
+
+    let seed = args.seed.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |time| time.as_nanos() as u64)
+    });
+
+    match width.checked_mul(height) {
+        Some(cells) if cells <= MAX_CELLS => Ok(World::random(
+            width,
+            height,
+            RANDOM_DENSITY,
+            &mut Rng::new(seed),
+        )),
+        _ => Err(WorldError::TooBig { width, height }),
+    }
+}
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let args = Args::parse(std::env::args().skip(1))?;
+
+    if args.help {
+        println!("{}", cli::USAGE);
+        return Ok(());
+    }
+
+    if let Some(path) = &args.check {
+        println!("{}", headless::check(path, args.format)?);
+        return Ok(());
+    }
+
+    if args.stdin && !args.inputs.is_empty() {
+        return Err("`--stdin` and `--input` can't be used together".into());
+    }
+
+    if args.random && (args.stdin || !args.inputs.is_empty()) {
+        return Err("`--random` can't be used with `--stdin` or `--input`".into());
+    }
+
+    // Standard input has to be read in full before the terminal is put into raw mode.
+    let initial = if !args.inputs.is_empty() {
+        let patterns = args
+            .inputs
+            .iter()
+            .map(|path| {
+                let error = |error: &dyn Error| format!("{}: {}", path.display(), error);
+                let input = fs::read_to_string(path).map_err(|e| error(&e))?;
+                let format = args.format.unwrap_or_else(|| Format::detect(&input));
+                format.parse(&input).map_err(|e| error(&e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let world = match (args.tile, patterns.as_slice()) {
+            // A single pattern is used as it is, unless it's explicitly being tiled.
+            (None, [pattern]) if args.slot.is_none() => pattern.clone(),
+            (tile, _) => {
+                let (columns, rows) = tile.unwrap_or((patterns.len(), 1));
+                let tiling = Tiling {
+                    columns,
+                    rows,
+                    spacing: args.spacing,
+                    slot: args.slot,
+                    overflow: if args.clip {
+                        Overflow::Drop
+                    } else {
+                        Overflow::Error
+                    },
+                };
+
+                tiling.compose(&patterns)?
+            }
+        };
+
+        Some((world, StartMode::Draw))
+    } else if args.stdin {
+        let mut stdin = std::io::stdin();
+
+        if stdin.is_terminal() {
+            return Err("`--stdin` needs a pattern to be piped in".into());
+        }
+
+        let mut input = String::new();
+        stdin.read_to_string(&mut input)?;
+
+        let format = args.format.unwrap_or_else(|| Format::detect(&input));
+        Some((format.parse(&input)?, StartMode::Draw))
+    } else if args.random {
+        Some((random_soup(&args)?, StartMode::Simulate))
+    } else {
+        None
+    };
+
+    let initial = initial.map(|(mut world, mode)| {
+        if let Some(edges) = args.edges {
+            world.set_edge_behavior(edges);
+        }
+
+        (world, mode)
+    });
+
+    // Patterns can say which rule they're meant for, but `--rule` takes priority.
+    let rule = args.rule.unwrap_or_else(|| {
+        initial
+            .as_ref()
+            .map_or_else(Rule::default, |(world, _)| world.rule())
+    });
+
+    if args.print {
+        let (world, _) = initial.ok_or("`--print` needs a pattern from `--input` or `--stdin`")?;
+        let mode = if args.minimal {
+            RenderMode::Minimal
+        } else {
+            RenderMode::Classic
+        };
+
+        println!(
+            "{}",
+            headless::final_generation(world, args.gens.unwrap_or(0), &rule, mode)
+        );
+        return Ok(());
+    }
+
+    if let Some(dir) = &args.dump_frames {
+        let (world, _) =
+            initial.ok_or("`--dump-frames` needs a pattern from `--input` or `--stdin`")?;
+        let generations = args.gens.ok_or("`--dump-frames` needs `--gens`")?;
+        let config = EngineConfig {
+            rule,
+            ..EngineConfig::default()
+        };
+
+        return Ok(headless::dump_frames(world, config, generations, dir)?);
+    }
+
+    let size_file = if args.no_state { None } else { state::path() };
+    let (initial_width, initial_height) = size_file
+        .as_deref()
+        .and_then(state::load_size)
+        .unwrap_or((8, 8));
 
-fn main() -> Result<(), Box<dyn Error>> {
     let stdout = std::io::stdout();
     let mut stdout = stdout.lock();
 
     App::new(Options {
         output: &mut stdout,
-        tick_length: Duration::from_millis(100),
+        tick_length: tick_length(),
+        initial,
+        origin: Origin::default(),
+        rule,
+        border: 0,
+        initial_width,
+        initial_height,
+        size_file,
+        ramp: None,
+        show_title: true,
+        screen_mode: if args.alternate_screen {
+            ScreenMode::AlternateScreen
+        } else {
+            ScreenMode::Inline
+        },
+        pause_on_cycle: args.pause_on_cycle,
+        max_cells: args.max_cells,
+        confirm_empty: !args.allow_empty,
+        blink_cursor: !args.no_blink,
+        loop_after: args.loop_after,
+        max_generations: args.max_generations,
+        generations_per_frame: args.gens_per_frame.unwrap_or(1),
+        grid_spacing: args.grid_spacing.unwrap_or(10),
+        seam: true,
+        downsample: if args.zoom_majority {
+            Downsample::Majority
+        } else {
+            Downsample::Any
+        },
+        render_mode: if args.minimal {
+            RenderMode::Minimal
+        } else {
+            RenderMode::Classic
+        },
+        status_position: if args.status_top {
+            StatusPosition::Top
+        } else {
+            StatusPosition::Bottom
+        },
+        theme: args.theme.unwrap_or_else(Theme::detect),
+        save_path: args.save.unwrap_or_else(|| "pattern.rle".into()),
+        hyperlinks: args.hyperlinks,
+        quit_key: args.quit_key.unwrap_or_default(),
+        events: None,
     })
     .run()
 }
+
+/// Reads the tick length from the `CONWAY_TICK_MS` environment variable, in milliseconds. If it isn't set, or isn't a
+/// valid number, then the default is used instead. A command line flag for the tick length should take precedence over
+/// this, if one is ever added.
+fn tick_length() -> Duration {
+    let Some(value) = std::env::var_os("CONWAY_TICK_MS") else {
+        return DEFAULT_TICK_LENGTH;
+    };
+
+    match value.to_str().and_then(|value| value.trim().parse().ok()) {
+        Some(millis) => Duration::from_millis(millis),
+        None => {
+            eprintln!(
+                "warning: ignoring invalid CONWAY_TICK_MS value {:?}, using {}ms",
+                value,
+                DEFAULT_TICK_LENGTH.as_millis()
+            );
+            DEFAULT_TICK_LENGTH
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Parses command line arguments given as string slices.
+    fn args(args: &[&str]) -> Args {
+        Args::parse(args.iter().map(|arg| arg.to_string())).unwrap()
+    }
+
+    #[test]
+    fn random_soups_come_from_their_size_and_seed() {
+        let args = args(&["--random", "--size", "20x10", "--seed", "42"]);
+        assert!(args.random);
+
+        let soup = random_soup(&args).unwrap();
+        assert_eq!(
+            soup,
+            World::random(20, 10, RANDOM_DENSITY, &mut Rng::new(42))
+        );
+        assert_eq!(soup, random_soup(&args).unwrap());
+    }
+
+    #[test]
+    fn random_soups_bigger_than_max_cells_are_errors() {
+        let huge = args(&["--random", "--size", "65536x65536"]);
+        assert!(random_soup(&huge).is_err());
+
+        let overflowing = args(&["--random", "--size", &format!("{}x2", usize::MAX)]);
+        assert!(random_soup(&overflowing).is_err());
+    }
+}
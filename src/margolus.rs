@@ -0,0 +1,128 @@
+//! Experimental support for block cellular automata using the Margolus neighborhood, where the world is split into 2x2
+//! blocks and each block is replaced as a whole. The blocks are offset by one cell diagonally on every other
+//! generation, so that information can move between them. Many block rules are reversible, unlike Conway's rules.
+//!
+//! This has nothing to do with `Rule`, and worlds ticked this way shouldn't be mixed with `World::tick`.
+
+use crate::{cell::Cell, world::World};
+
+/// Describes how each 2x2 block changes from one generation to the next.
+///
+/// A block is written as 4 bits, with the top-left cell in the lowest bit, followed by the top-right, bottom-left and
+/// bottom-right cells. The rule is a table mapping each of the 16 possible blocks onto the block that replaces it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlockRule {
+    table: [u8; 16],
+}
+
+impl BlockRule {
+    /// Constructs a new `BlockRule` from its table. Only the lowest 4 bits of each entry are used.
+    pub fn new(table: [u8; 16]) -> Self {
+        BlockRule {
+            table: table.map(|block| block & 0b1111),
+        }
+    }
+
+    /// The Critters rule. Blocks with exactly 2 live cells stay the same, and every other block is inverted. Blocks
+    /// that had 3 live cells are also rotated by 180 degrees.
+    pub fn critters() -> Self {
+        let mut table = [0; 16];
+
+        for (block, entry) in table.iter_mut().enumerate() {
+            let block = block as u8;
+
+            *entry = match block.count_ones() {
+                2 => block,
+                3 => rotate(!block & 0b1111),
+                _ => !block & 0b1111,
+            };
+        }
+
+        BlockRule::new(table)
+    }
+
+    /// The billiard ball machine rule. A lone live cell moves to the opposite corner of its block, and a diagonal pair
+    /// of live cells turns into the other diagonal pair. Every other block stays the same.
+    pub fn billiard_ball() -> Self {
+        let mut table: [u8; 16] = std::array::from_fn(|block| block as u8);
+
+        table[0b0001] = 0b1000;
+        table[0b1000] = 0b0001;
+        table[0b0010] = 0b0100;
+        table[0b0100] = 0b0010;
+        table[0b1001] = 0b0110;
+        table[0b0110] = 0b1001;
+
+        BlockRule::new(table)
+    }
+
+    /// The block that replaces `block`.
+    pub fn apply(&self, block: u8) -> u8 {
+        self.table[(block & 0b1111) as usize]
+    }
+}
+
+/// Rotates a block by 180 degrees, which swaps opposite corners.
+fn rotate(block: u8) -> u8 {
+    (0..4).fold(0, |rotated, bit| {
+        rotated | ((block >> bit) & 1) << (3 - bit)
+    })
+}
+
+impl World {
+    /// Computes the next generation of the world under a block rule. The blocks start at the top-left corner when
+    /// `odd` is false, and one cell down and to the right of it when `odd` is true, so this should alternate between the
+    /// two from one generation to the next.
+    ///
+    /// Blocks that hang off the edge of the world wrap around if the world wraps, and otherwise see the missing cells as
+    /// dead, with whatever the rule puts in them being thrown away. Wrapping only lines the blocks up properly when the
+    /// wrapped axes have an even length. Markers are left where they are, and count as dead cells.
+    pub fn parity_tick(&self, rule: &BlockRule, odd: bool) -> World {
+        let mut next = self.clone();
+        let offset = odd as isize;
+
+        for top in (-offset..self.height() as isize).step_by(2) {
+            for left in (-offset..self.width() as isize).step_by(2) {
+                let positions = [(0, 0), (1, 0), (0, 1), (1, 1)]
+                    .map(|(x, y)| self.block_position(left + x, top + y));
+
+                let block = positions
+                    .iter()
+                    .enumerate()
+                    .fold(0, |block, (bit, position)| {
+                        let alive = position.is_some_and(|position| self[position].alive());
+                        block | (alive as u8) << bit
+                    });
+
+                let replacement = rule.apply(block);
+
+                for (bit, position) in positions.iter().enumerate() {
+                    let Some(position) = *position else { continue };
+
+                    if !self[position].marker() {
+                        next[position] = Cell::from_bool((replacement >> bit) & 1 == 1);
+                    }
+                }
+            }
+        }
+
+        next
+    }
+
+    /// Works out where a cell in a block ends up, wrapping around if the world does. This is `None` if the cell is
+    /// outside of the world.
+    fn block_position(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        let edges = self.edge_behavior();
+        let axis = |coordinate: isize, length: usize, wrap: bool| match wrap {
+            true if length > 0 => Some(coordinate.rem_euclid(length as isize) as usize),
+            _ => usize::try_from(coordinate)
+                .ok()
+                .filter(|&coordinate| coordinate < length),
+        };
+
+        Some((
+            axis(x, self.width(), edges.wraps_horizontally())?,
+            axis(y, self.height(), edges.wraps_vertically())?,
+        ))
+    }
+}
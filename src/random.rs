@@ -0,0 +1,130 @@
+//! A small seeded random number generator, for making random soups that can be reproduced exactly from their seed. This
+//! isn't suitable for anything that needs to be unpredictable.
+
+use crate::{
+    cell::Cell,
+    world::{Rect, World},
+};
+
+/// A SplitMix64 generator. The same seed always gives the same sequence of numbers, on every platform.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number between 0 (inclusive) and 1 (exclusive), with every possible value being equally likely.
+    pub fn next_f64(&mut self) -> f64 {
+        // An `f64` has 53 bits of precision, so the rest of the bits are thrown away.
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl World {
+    /// Constructs a new `World` where each cell is alive with a chance of `density`, which is clamped to between 0
+    /// and 1. The cells are filled in row-major order, so the same seed and size always give the same world.
+    pub fn random(width: usize, height: usize, density: f64, rng: &mut Rng) -> Self {
+        let density = density.clamp(0.0, 1.0);
+        let mut world = World::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                world[(x, y)] = Cell::from_bool(rng.next_f64() < density);
+            }
+        }
+
+        world
+    }
+
+    /// Like `World::random`, but with a different density for each region. Where regions overlap, the last one wins,
+    /// and cells that aren't in any region are dead. The regions are clipped to the edges of the world.
+    pub fn random_regions(
+        width: usize,
+        height: usize,
+        regions: &[(Rect, f64)],
+        rng: &mut Rng,
+    ) -> Self {
+        let mut world = World::new(width, height);
+
+        for y in 0..height {
+            for x in 0..width {
+                let density = regions
+                    .iter()
+                    .rev()
+                    .find(|(region, _)| region.contains((x, y)))
+                    .map(|&(_, density)| density.clamp(0.0, 1.0));
+
+                if let Some(density) = density {
+                    world[(x, y)] = Cell::from_bool(rng.next_f64() < density);
+                }
+            }
+        }
+
+        world
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The fraction of the cells in `region` that are alive.
+    fn density(world: &World, region: Rect) -> f64 {
+        let live = (region.top..region.top + region.height)
+            .flat_map(|y| (region.left..region.left + region.width).map(move |x| (x, y)))
+            .filter(|&position| world[position].alive())
+            .count();
+
+        live as f64 / (region.width * region.height) as f64
+    }
+
+    #[test]
+    fn each_region_gets_its_own_density() {
+        let everywhere = Rect {
+            left: 0,
+            top: 0,
+            width: 100,
+            height: 50,
+        };
+        let center = Rect {
+            left: 25,
+            top: 25,
+            width: 50,
+            height: 50,
+        };
+        let world = World::random_regions(
+            100,
+            100,
+            &[(everywhere, 0.1), (center, 0.8)],
+            &mut Rng::new(7),
+        );
+
+        // Part of the first region that the second one doesn't cover, and part of the world that neither region does.
+        let top = Rect {
+            height: 25,
+            ..everywhere
+        };
+        let uncovered = Rect {
+            top: 50,
+            width: 25,
+            ..everywhere
+        };
+
+        assert!((density(&world, center) - 0.8).abs() < 0.05);
+        assert!((density(&world, top) - 0.1).abs() < 0.05);
+        assert_eq!(density(&world, uncovered), 0.0);
+    }
+}
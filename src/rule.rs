@@ -0,0 +1,178 @@
+use std::{fmt::Display, str::FromStr};
+
+/// Describes how each cell's next state is decided from its current state and the states of its neighbors. Rules are
+/// ordered so that worlds, which each have a rule, can be ordered too.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rule {
+    /// An outer totalistic rule, describing which live neighbor counts cause a dead cell to be born and a live cell to
+    /// survive. The cell itself isn't counted. This is written as `B3/S23`.
+    BirthSurvival {
+        birth: [bool; 9],
+        survival: [bool; 9],
+    },
+    /// A voting rule, where the cell itself is counted along with its eight neighbors. The cell is alive in the next
+    /// generation if the total number of live cells is one of the given totals, regardless of its current state. This
+    /// is written as `V` followed by the totals, such as `V56789`.
+    Voting { totals: [bool; 10] },
+}
+
+impl Rule {
+    /// Constructs a new birth/survival `Rule` from the neighbor counts that cause a birth and the neighbor counts that
+    /// cause survival. Counts above 8 are ignored, since a cell can't have more than 8 neighbors.
+    pub fn new(birth: &[usize], survival: &[usize]) -> Self {
+        Rule::BirthSurvival {
+            birth: table(birth),
+            survival: table(survival),
+        }
+    }
+
+    /// Constructs a new voting `Rule` from the totals (including the cell itself) that leave a cell alive. Totals above
+    /// 9 are ignored.
+    pub fn voting(totals: &[usize]) -> Self {
+        Rule::Voting {
+            totals: table(totals),
+        }
+    }
+
+    /// The rule used by Conway's Game of Life, B3/S23.
+    pub fn conway() -> Self {
+        Rule::new(&[3], &[2, 3])
+    }
+
+    /// The majority voting rule, where a cell takes on whichever state most of its 3x3 neighborhood has.
+    pub fn vote() -> Self {
+        Rule::voting(&[5, 6, 7, 8, 9])
+    }
+
+    /// The "anneal" rule, a voting rule where the middle totals are swapped around, which makes boundaries between
+    /// regions smooth out over time rather than freezing.
+    pub fn anneal() -> Self {
+        Rule::voting(&[4, 6, 7, 8, 9])
+    }
+}
+
+impl Default for Rule {
+    fn default() -> Self {
+        Rule::conway()
+    }
+}
+
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = |table: &[bool]| -> String {
+            (0..table.len())
+                .filter(|&count| table[count])
+                .map(|count| char::from_digit(count as u32, 10).unwrap())
+                .collect()
+        };
+
+        match self {
+            Rule::BirthSurvival { birth, survival } => {
+                write!(f, "B{}/S{}", digits(birth), digits(survival))
+            }
+            Rule::Voting { totals } => write!(f, "V{}", digits(totals)),
+        }
+    }
+}
+
+impl FromStr for Rule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid rule `{}`", s);
+        let digits = |text: &str, max: u32| -> Result<Vec<usize>, String> {
+            text.chars()
+                .map(|c| c.to_digit(10).filter(|&digit| digit <= max))
+                .map(|digit| digit.map(|digit| digit as usize).ok_or_else(invalid))
+                .collect()
+        };
+
+        let upper = s.trim().to_ascii_uppercase();
+
+        if let Some(totals) = upper.strip_prefix('V') {
+            return Ok(Rule::voting(&digits(totals, 9)?));
+        }
+
+        let (birth, survival) = upper.split_once('/').ok_or_else(invalid)?;
+        let birth = birth.strip_prefix('B').ok_or_else(invalid)?;
+        let survival = survival.strip_prefix('S').ok_or_else(invalid)?;
+
+        Ok(Rule::new(&digits(birth, 8)?, &digits(survival, 8)?))
+    }
+}
+
+/// Builds a lookup table with an entry for each count up to `N - 1`, ignoring any counts that don't fit.
+fn table<const N: usize>(counts: &[usize]) -> [bool; N] {
+    let mut table = [false; N];
+
+    for &count in counts.iter().filter(|&&count| count < N) {
+        table[count] = true;
+    }
+
+    table
+}
+
+/// Decides whether a cell is alive in the next generation, given whether it's currently alive and how many live
+/// neighbors it has.
+pub fn next_state(alive: bool, live_neighbors: usize, rule: &Rule) -> bool {
+    match rule {
+        Rule::BirthSurvival { birth, survival } => {
+            let table = if alive { survival } else { birth };
+            table.get(live_neighbors).copied().unwrap_or(false)
+        }
+        Rule::Voting { totals } => {
+            let total = live_neighbors + alive as usize;
+            totals.get(total).copied().unwrap_or(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    #[test]
+    fn next_state_follows_conway() {
+        for live_neighbors in 0..=8 {
+            assert_eq!(
+                next_state(false, live_neighbors, &Rule::conway()),
+                live_neighbors == 3,
+                "a dead cell with {} live neighbor(s)",
+                live_neighbors
+            );
+            assert_eq!(
+                next_state(true, live_neighbors, &Rule::conway()),
+                live_neighbors == 2 || live_neighbors == 3,
+                "a live cell with {} live neighbor(s)",
+                live_neighbors
+            );
+        }
+    }
+
+    #[test]
+    fn voting_counts_the_cell_itself() {
+        // A total of 5 is a majority of the 3x3 neighborhood, whether or not the cell itself is one of the 5.
+        assert!(next_state(true, 4, &Rule::vote()));
+        assert!(next_state(false, 5, &Rule::vote()));
+        assert!(!next_state(true, 3, &Rule::vote()));
+        assert!(!next_state(false, 4, &Rule::vote()));
+
+        // Anneal swaps the totals of 4 and 5 around.
+        assert!(next_state(false, 4, &Rule::anneal()));
+        assert!(!next_state(true, 4, &Rule::anneal()));
+    }
+
+    #[test]
+    fn majority_voting_rounds_off_a_square() {
+        let square = Format::Plaintext
+            .parse(".....\n.OOO.\n.OOO.\n.OOO.\n.....")
+            .unwrap();
+        let rounded = Format::Plaintext
+            .parse(".....\n..O..\n.OOO.\n..O..\n.....")
+            .unwrap();
+
+        // Each corner of the square only has 4 live cells in its neighborhood, counting itself, so it's outvoted.
+        assert_eq!(square.tick_with(&Rule::vote()), rounded);
+    }
+}
@@ -0,0 +1,199 @@
+//! A headless search through random soups, for finding patterns worth a closer look. This is only available with the
+//! `soup` feature, and has nothing to do with the terminal UI.
+
+use crate::{
+    analysis::{Cycle, CycleDetector},
+    cell::Cell,
+    random::Rng,
+    rule::Rule,
+    world::World,
+};
+
+/// What happened to a single random soup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SoupResult {
+    /// The seed the soup was made from. Passing a fresh `Rng` with this seed to `World::random` (with the same size and
+    /// density) gives back the exact same soup.
+    pub seed: u64,
+    /// How many generations were simulated. If the soup settled, this is when its cycle was first seen to repeat.
+    pub generations: usize,
+    /// The population of the last generation simulated.
+    pub population: usize,
+    /// The cycle the soup settled into, or `None` if it was still changing after the last generation.
+    pub cycle: Option<Cycle>,
+    /// How many spaceships were still flying around in the last generation. Each clump of live cells is checked on its
+    /// own, so a spaceship counts even if the rest of the soup is still changing, but not while it's touching
+    /// something else.
+    pub spaceships: usize,
+}
+
+impl SoupResult {
+    /// The period of the cycle the soup settled into, if it settled at all.
+    pub fn period(&self) -> Option<usize> {
+        self.cycle.map(|cycle| cycle.period)
+    }
+
+    /// Whether the soup produced at least one spaceship that was still around in the last generation.
+    pub fn is_spaceship(&self) -> bool {
+        self.spaceships > 0
+    }
+}
+
+/// Runs `trials` random soups of the given size and density under `rule`, simulating each one until it settles into a
+/// cycle (including spaceships) or until `max_gens` generations have passed. The same seed always gives the same
+/// results, and each result records the seed of its own soup so that interesting ones can be recreated on their own.
+pub fn soup_search(
+    width: usize,
+    height: usize,
+    density: f64,
+    rule: &Rule,
+    max_gens: usize,
+    trials: usize,
+    seed: u64,
+) -> Vec<SoupResult> {
+    let mut seeds = Rng::new(seed);
+
+    (0..trials)
+        .map(|_| {
+            let seed = seeds.next_u64();
+            let soup = World::random(width, height, density, &mut Rng::new(seed));
+            run_soup(soup, rule, max_gens, seed)
+        })
+        .collect()
+}
+
+fn run_soup(mut world: World, rule: &Rule, max_gens: usize, seed: u64) -> SoupResult {
+    let mut detector = CycleDetector::default();
+    detector.observe(&world);
+
+    let mut result = SoupResult {
+        seed,
+        generations: 0,
+        population: world.population(),
+        cycle: None,
+        spaceships: 0,
+    };
+
+    for generation in 1..=max_gens {
+        world = world.tick_with(rule);
+        result.generations = generation;
+        result.cycle = detector.observe(&world);
+
+        if result.cycle.is_some() {
+            break;
+        }
+    }
+
+    result.population = world.population();
+    result.spaceships = clumps(&world)
+        .iter()
+        .filter(|clump| is_spaceship(clump, rule))
+        .count();
+
+    result
+}
+
+/// How close two live cells have to be to count as part of the same clump. Cells that only touch diagonally are close
+/// enough, and so are cells with a single dead cell between them, since some spaceships (like the lightweight spaceship)
+/// have parts that don't touch at all.
+const CLUMP_REACH: usize = 2;
+
+/// Splits the live cells of a soup into clumps, using a flood fill like `World::cluster_count` but with a longer reach.
+/// Soups never wrap around, so neither do the clumps.
+fn clumps(world: &World) -> Vec<Vec<(usize, usize)>> {
+    let (width, height) = (world.width(), world.height());
+    let mut visited = vec![false; width * height];
+    let mut pending = Vec::new();
+    let mut clumps = Vec::new();
+
+    for start in world.live_cells() {
+        if visited[start.1 * width + start.0] {
+            continue;
+        }
+
+        let mut clump = Vec::new();
+        visited[start.1 * width + start.0] = true;
+        pending.push(start);
+
+        while let Some((x, y)) = pending.pop() {
+            clump.push((x, y));
+
+            let columns = x.saturating_sub(CLUMP_REACH)..(x + CLUMP_REACH + 1).min(width);
+            let rows = y.saturating_sub(CLUMP_REACH)..(y + CLUMP_REACH + 1).min(height);
+
+            for y in rows {
+                for x in columns.clone() {
+                    if !visited[y * width + x] && world[(x, y)].alive() {
+                        visited[y * width + x] = true;
+                        pending.push((x, y));
+                    }
+                }
+            }
+        }
+
+        clumps.push(clump);
+    }
+
+    clumps
+}
+
+/// Checks whether a clump of cells is a spaceship, by simulating it on its own. The clump gets enough empty space around
+/// it that even a spaceship moving at the speed of light can't reach the edge before its cycle is found.
+fn is_spaceship(clump: &[(usize, usize)], rule: &Rule) -> bool {
+    let generations = CycleDetector::DEFAULT_CAPACITY;
+    let left = clump.iter().map(|&(x, _)| x).min().unwrap_or(0);
+    let top = clump.iter().map(|&(_, y)| y).min().unwrap_or(0);
+    let right = clump.iter().map(|&(x, _)| x).max().unwrap_or(0);
+    let bottom = clump.iter().map(|&(_, y)| y).max().unwrap_or(0);
+
+    let mut world = World::new(
+        right - left + 1 + 2 * generations,
+        bottom - top + 1 + 2 * generations,
+    );
+
+    for &(x, y) in clump {
+        world[(x - left + generations, y - top + generations)] = Cell::Alive;
+    }
+
+    let mut detector = CycleDetector::default();
+    detector.observe(&world);
+
+    for _ in 0..generations {
+        world = world.tick_with(rule);
+
+        if let Some(cycle) = detector.observe(&world) {
+            return cycle.is_spaceship();
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    #[test]
+    fn spaceships_count_even_when_the_rest_of_the_soup_is_still_changing() {
+        // A glider and a lightweight spaceship heading away from a blinker, which stops the whole world from ever
+        // repeating.
+        let world = Format::Rle
+            .parse("x = 60, y = 40\nbo$2bo$3o10$30bo2bo$29bo$29bo3bo$29b4o20$50b3o!")
+            .unwrap();
+        let result = run_soup(world, &Rule::conway(), 8, 0);
+
+        assert_eq!(result.cycle, None);
+        assert_eq!(result.spaceships, 2);
+        assert!(result.is_spaceship());
+    }
+
+    #[test]
+    fn still_lifes_are_not_spaceships() {
+        let world = Format::Rle.parse("x = 10, y = 10\n2$2b2o$2b2o!").unwrap();
+        let result = run_soup(world, &Rule::conway(), 8, 0);
+
+        assert_eq!(result.spaceships, 0);
+        assert!(!result.is_spaceship());
+    }
+}
@@ -0,0 +1,39 @@
+//! A small state file for remembering things between runs, which currently is just the last grid size chosen in
+//! `Scale`. Anything going wrong with the file (it not existing, being unreadable, or being corrupt) is treated the same
+//! as there being no saved state at all.
+
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Where the state file lives, which is `conway/size` inside the user's config directory. This is `None` if the config
+/// directory can't be found.
+pub fn path() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+
+    Some(config.join("conway").join("size"))
+}
+
+/// Reads a grid size from the state file at `path`, written as `<width>x<height>`. Sizes with a zero in them are
+/// treated as corrupt, since the grid must always be at least 1 by 1.
+pub fn load_size(path: &Path) -> Option<(usize, usize)> {
+    let contents = fs::read_to_string(path).ok()?;
+    let (width, height) = contents.trim().split_once('x')?;
+    let (width, height) = (width.parse().ok()?, height.parse().ok()?);
+
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+/// Writes a grid size to the state file at `path`, creating its directory if needed.
+pub fn save_size(path: &Path, (width, height): (usize, usize)) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    fs::write(path, format!("{}x{}\n", width, height))
+}
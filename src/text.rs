@@ -0,0 +1,84 @@
+//! Helpers for working with text that may contain ANSI escape sequences, which take up no space on the terminal.
+
+/// Counts the characters in `text` that are actually visible on a terminal, skipping over any escape sequences.
+pub fn visible_width(text: &str) -> usize {
+    Segments::new(text)
+        .filter(|segment| !segment.escape)
+        .count()
+}
+
+/// Truncates `text` so that it takes up at most `width` visible characters. Escape sequences are always kept, so that
+/// any styling is still reset properly after the cut-off point.
+pub fn truncate_visible(text: &str, width: usize) -> String {
+    let mut visible = 0;
+    let mut result = String::with_capacity(text.len());
+
+    for segment in Segments::new(text) {
+        if segment.escape {
+            result.push_str(segment.text);
+        } else if visible < width {
+            result.push_str(segment.text);
+            visible += 1;
+        }
+    }
+
+    result
+}
+
+/// Either a single visible character, or a whole escape sequence.
+struct Segment<'a> {
+    text: &'a str,
+    escape: bool,
+}
+
+struct Segments<'a> {
+    text: &'a str,
+}
+
+impl<'a> Segments<'a> {
+    fn new(text: &'a str) -> Self {
+        Segments { text }
+    }
+}
+
+impl<'a> Iterator for Segments<'a> {
+    type Item = Segment<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chars = self.text.char_indices();
+        let (_, first) = chars.next()?;
+
+        let end = if first != '\x1b' {
+            first.len_utf8()
+        } else {
+            match chars.next() {
+                // A CSI sequence runs until its final byte, which is in the range `@` to `~`.
+                Some((_, '[')) => chars
+                    .find(|(_, c)| ('@'..='~').contains(c))
+                    .map_or(self.text.len(), |(index, c)| index + c.len_utf8()),
+                // An OSC sequence (such as a hyperlink) runs until either a bell or a string terminator, `ESC \`.
+                Some((_, ']')) => {
+                    let rest = &self.text[2..];
+                    let bell = rest.find('\x07').map(|index| index + 1);
+                    let terminator = rest.find("\x1b\\").map(|index| index + 2);
+
+                    [bell, terminator]
+                        .into_iter()
+                        .flatten()
+                        .min()
+                        .map_or(self.text.len(), |end| 2 + end)
+                }
+                Some((index, c)) => index + c.len_utf8(),
+                None => first.len_utf8(),
+            }
+        };
+
+        let (text, rest) = self.text.split_at(end);
+        self.text = rest;
+
+        Some(Segment {
+            text,
+            escape: first == '\x1b',
+        })
+    }
+}
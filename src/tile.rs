@@ -0,0 +1,145 @@
+//! Composing several patterns into one big world by laying them out in a grid.
+
+use crate::{
+    format::Overflow,
+    world::{World, MAX_CELLS},
+};
+
+/// Describes how to lay patterns out in a grid of equally sized slots.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tiling {
+    pub columns: usize,
+    pub rows: usize,
+    /// The number of dead cells between neighboring slots, and between the slots and the edge of the world.
+    pub spacing: usize,
+    /// The width and height of each slot. When this is `None`, slots are just big enough to fit the largest pattern.
+    pub slot: Option<(usize, usize)>,
+    /// What to do with patterns that are bigger than their slot. `Overflow::Drop` clips them to the slot.
+    pub overflow: Overflow,
+}
+
+impl Tiling {
+    /// Lays `patterns` out from left to right and top to bottom, centering the live cells of each one within its slot.
+    /// When there are fewer patterns than slots, the patterns are repeated until every slot is filled. This errors
+    /// rather than allocating if the tiled world would have more than `MAX_CELLS` cells.
+    pub fn compose(&self, patterns: &[World]) -> Result<World, String> {
+        if patterns.is_empty() || self.columns == 0 || self.rows == 0 {
+            return Err("there's nothing to tile".to_string());
+        }
+
+        let patterns: Vec<World> = patterns.iter().map(World::trimmed).collect();
+        let (slot_width, slot_height) = self.slot.unwrap_or_else(|| {
+            let width = patterns.iter().map(World::width).max().unwrap_or(0);
+            let height = patterns.iter().map(World::height).max().unwrap_or(0);
+            (width, height)
+        });
+
+        // The counts and sizes come from the command line, so they could be big enough to overflow.
+        let length = |count: usize, slot: usize| {
+            count
+                .checked_mul(slot)?
+                .checked_add(count.checked_add(1)?.checked_mul(self.spacing)?)
+        };
+        let too_big = || format!("the tiled world would have more than {} cells", MAX_CELLS);
+
+        let width = length(self.columns, slot_width).ok_or_else(too_big)?;
+        let height = length(self.rows, slot_height).ok_or_else(too_big)?;
+        let slots = self.columns.checked_mul(self.rows).ok_or_else(too_big)?;
+        let mut world = World::try_new(width, height).map_err(|error| error.to_string())?;
+
+        for (index, pattern) in patterns.iter().cycle().take(slots).enumerate() {
+            let fits = pattern.width() <= slot_width && pattern.height() <= slot_height;
+
+            if !fits && self.overflow == Overflow::Error {
+                return Err(format!(
+                    "a {}x{} pattern doesn't fit in a {}x{} tile",
+                    pattern.width(),
+                    pattern.height(),
+                    slot_width,
+                    slot_height
+                ));
+            }
+
+            // Placing the pattern into a world of its own first means that clipping never lets it spill into the
+            // neighboring slots.
+            let mut slot = World::new(slot_width, slot_height);
+            slot.overlay(
+                pattern,
+                (
+                    slot_width.saturating_sub(pattern.width()) / 2,
+                    slot_height.saturating_sub(pattern.height()) / 2,
+                ),
+            );
+
+            let (column, row) = (index % self.columns, index / self.columns);
+            world.overlay(
+                &slot,
+                (
+                    self.spacing + column * (slot_width + self.spacing),
+                    self.spacing + row * (slot_height + self.spacing),
+                ),
+            );
+        }
+
+        Ok(world)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::format::Format;
+
+    /// Builds a world out of plaintext rows, where `O` is a live cell and `.` is a dead one.
+    fn world(rows: &str) -> World {
+        Format::Plaintext.parse(rows).unwrap()
+    }
+
+    /// A tiling with slots just big enough for the largest pattern, which errors on patterns that don't fit.
+    fn tiling(columns: usize, rows: usize, spacing: usize) -> Tiling {
+        Tiling {
+            columns,
+            rows,
+            spacing,
+            slot: None,
+            overflow: Overflow::Error,
+        }
+    }
+
+    #[test]
+    fn patterns_are_centered_in_their_slots() {
+        let block = world("OO\nOO");
+        let cell = world("O");
+        let tiled = tiling(2, 1, 1).compose(&[block, cell]).unwrap();
+
+        assert_eq!(tiled, world(".......\n.OO.O..\n.OO....\n......."));
+    }
+
+    #[test]
+    fn patterns_repeat_to_fill_every_slot() {
+        let tiled = tiling(3, 2, 0).compose(&[world("O")]).unwrap();
+
+        assert_eq!((tiled.width(), tiled.height()), (3, 2));
+        assert!(tiled.is_full());
+    }
+
+    #[test]
+    fn patterns_bigger_than_their_slot_error_or_clip() {
+        let line = [world("OOO")];
+        let mut tiling = Tiling {
+            slot: Some((2, 1)),
+            ..tiling(1, 1, 0)
+        };
+
+        assert!(tiling.compose(&line).is_err());
+
+        tiling.overflow = Overflow::Drop;
+        assert_eq!(tiling.compose(&line).unwrap(), world("OO"));
+    }
+
+    #[test]
+    fn huge_or_empty_tilings_are_errors() {
+        assert!(tiling(usize::MAX, 2, 1).compose(&[world("O")]).is_err());
+        assert!(tiling(0, 2, 1).compose(&[world("O")]).is_err());
+    }
+}
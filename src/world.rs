@@ -1,29 +1,248 @@
 use std::{
-    alloc::Layout,
+    cell::RefCell,
+    error::Error,
     fmt::{Display, Write},
     ops::{Index, IndexMut},
+    str::FromStr,
 };
 
-use crate::cell::{Cell, LocatedCell, Position};
+use crate::{
+    cell::{Cell, LocatedCell, Position, RenderMode},
+    format::Overflow,
+    rule::{self, Rule},
+};
+
+/// The most cells that `World::try_new` allows, which is enough for a 16384x16384 world. Anything bigger takes gigabytes
+/// of memory, so when a pattern file asks for it, it's almost certainly a mistake (or a file made to cause trouble).
+pub const MAX_CELLS: usize = 1 << 28;
+
+thread_local! {
+    // The buffer that `World::step` ticks into, which is swapped with the world afterwards so that the old generation's
+    // buffer is reused by the next step rather than allocating a new one every time.
+    static STEP_BUFFER: RefCell<World> = RefCell::default();
+}
+
+/// Describes how the edges of a `World` are treated when counting neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum EdgeBehavior {
+    /// Everything beyond the edges of the world is considered dead.
+    #[default]
+    Dead,
+    /// The edges of the world wrap around to the opposite side, making it a torus.
+    ///
+    /// A cell's neighbors are the distinct cells reachable by the eight neighbor offsets, other than the cell itself. On
+    /// worlds less than 3 cells wide or high, several offsets wrap around to the same cell, but that cell is still only
+    /// counted once. This means that a 1x1 world has no neighbors at all, and every cell in a 2x2 world has exactly 3.
+    Wrap,
+    /// The left and right edges wrap around to each other, making the world a cylinder. Everything beyond the top and
+    /// bottom edges is considered dead.
+    WrapHorizontal,
+    /// The top and bottom edges wrap around to each other, making the world a cylinder. Everything beyond the left and
+    /// right edges is considered dead.
+    WrapVertical,
+}
+
+impl FromStr for EdgeBehavior {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "dead" => Ok(EdgeBehavior::Dead),
+            "wrap" => Ok(EdgeBehavior::Wrap),
+            "wrap-horizontal" => Ok(EdgeBehavior::WrapHorizontal),
+            "wrap-vertical" => Ok(EdgeBehavior::WrapVertical),
+            _ => Err(format!("unknown edge behavior `{}`", s)),
+        }
+    }
+}
+
+impl EdgeBehavior {
+    /// Returns the edge behavior that comes after this one, wrapping around after the last.
+    pub fn next(&self) -> Self {
+        match self {
+            EdgeBehavior::Dead => EdgeBehavior::Wrap,
+            EdgeBehavior::Wrap => EdgeBehavior::WrapHorizontal,
+            EdgeBehavior::WrapHorizontal => EdgeBehavior::WrapVertical,
+            EdgeBehavior::WrapVertical => EdgeBehavior::Dead,
+        }
+    }
 
-#[derive(Debug, Clone, PartialEq, Default)]
+    pub fn name(&self) -> &'static str {
+        match self {
+            EdgeBehavior::Dead => "Dead",
+            EdgeBehavior::Wrap => "Wrap",
+            EdgeBehavior::WrapHorizontal => "Wrap horizontally",
+            EdgeBehavior::WrapVertical => "Wrap vertically",
+        }
+    }
+
+    /// Whether the left and right edges wrap around to each other.
+    pub fn wraps_horizontally(&self) -> bool {
+        matches!(self, EdgeBehavior::Wrap | EdgeBehavior::WrapHorizontal)
+    }
+
+    /// Whether the top and bottom edges wrap around to each other.
+    pub fn wraps_vertically(&self) -> bool {
+        matches!(self, EdgeBehavior::Wrap | EdgeBehavior::WrapVertical)
+    }
+}
+
+/// Optional information about a pattern, such as the name and comments from the file it was loaded from. This is kept
+/// around so that it can be written back out, but has no effect on the simulation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Metadata {
+    pub name: Option<String>,
+    pub comments: Vec<String>,
+}
+
+/// A rectangular region of a world, which may reach past its edges.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Rect {
+    pub left: usize,
+    pub top: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn contains(&self, (x, y): (usize, usize)) -> bool {
+        (self.left..self.left.saturating_add(self.width)).contains(&x)
+            && (self.top..self.top.saturating_add(self.height)).contains(&y)
+    }
+}
+
+/// How `World::downsampled` decides whether a block of cells is alive.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Downsample {
+    /// A block is alive if any of its cells are. This makes sure that nothing disappears, even a single live cell.
+    #[default]
+    Any,
+    /// A block is alive if more than half of its cells are, which shows the overall shape of dense patterns better.
+    Majority,
+}
+
+/// A cell changing state from one generation to the next, as reported by `World::tick_observe`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CellEvent {
+    /// The dead cell at this position came to life.
+    Born((usize, usize)),
+    /// The live cell at this position died.
+    Died((usize, usize)),
+}
+
+/// An error encountered while building a `World` out of something else, such as another world.
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorldError {
+    /// A row didn't have the same number of cells as the first row.
+    RaggedRows {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A region reached past the edges of the world it was taken from.
+    OutOfBounds {
+        top_left: (usize, usize),
+        width: usize,
+        height: usize,
+    },
+    /// The number of cells given for a world didn't match its width and height.
+    WrongCellCount {
+        width: usize,
+        height: usize,
+        found: usize,
+    },
+    /// A world would have had more than `MAX_CELLS` cells.
+    TooBig { width: usize, height: usize },
+}
+
+impl Display for WorldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WorldError::RaggedRows {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {} has {} cell(s), but the rows before it have {}",
+                row, found, expected
+            ),
+            WorldError::OutOfBounds {
+                top_left: (x, y),
+                width,
+                height,
+            } => write!(
+                f,
+                "the {}x{} region at ({}, {}) goes past the edge of the world",
+                width, height, x, y
+            ),
+            WorldError::WrongCellCount {
+                width,
+                height,
+                found,
+            } => write!(
+                f,
+                "a {}x{} world can't be made out of {} cell(s)",
+                width, height, found
+            ),
+            WorldError::TooBig { width, height } => write!(
+                f,
+                "a {}x{} world is too big, since worlds can have at most {} cells",
+                width, height, MAX_CELLS
+            ),
+        }
+    }
+}
+
+impl Error for WorldError {}
+
+/// Worlds are ordered by their width, then their height, then their cells in row-major order. Worlds with the same cells
+/// are ordered by their edge behavior, then their metadata, and then their rule, so that the ordering agrees with `==`,
+/// which makes worlds usable as keys in a `BTreeMap` or `BTreeSet`.
+///
+/// Each world carries the rule that `tick` simulates it under, which is Conway's rule unless it's changed with
+/// `with_rule` or `set_rule`. The `_with` methods (such as `tick_with`) take a rule instead, and ignore the world's own
+/// one.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Default)]
 pub struct World {
+    // The order of these fields decides the order of worlds, so it shouldn't be changed.
     width: usize,
     height: usize,
     cells: Box<[Cell]>,
+    edge_behavior: EdgeBehavior,
+    metadata: Metadata,
+    rule: Rule,
 }
 
 impl World {
     /// Constructs a new `World` with the specified width and height. This does not allocate if the world would contain
     /// 0 cells.
+    ///
+    /// # Panics
+    /// Panics if the number of cells doesn't fit in a `usize`. Use `try_new` for sizes that come from outside the
+    /// program.
     pub fn new(width: usize, height: usize) -> Self {
-        let size = width * height;
+        let size = width
+            .checked_mul(height)
+            .expect("the number of cells in the world doesn't fit in a usize");
         let cells = vec![Cell::Dead; size].into_boxed_slice();
 
         World {
             width,
             height,
             cells,
+            edge_behavior: EdgeBehavior::default(),
+            metadata: Metadata::default(),
+            rule: Rule::default(),
+        }
+    }
+
+    /// Like `World::new`, but errors rather than allocating if the world would have more than `MAX_CELLS` cells, so that
+    /// sizes read from a file or the command line can't exhaust memory.
+    pub fn try_new(width: usize, height: usize) -> Result<Self, WorldError> {
+        match width.checked_mul(height) {
+            Some(cells) if cells <= MAX_CELLS => Ok(World::new(width, height)),
+            _ => Err(WorldError::TooBig { width, height }),
         }
     }
 
@@ -31,6 +250,211 @@ impl World {
         self.into_iter()
     }
 
+    /// Kills every cell within `border` cells of the edge of the world.
+    pub fn kill_border(&mut self, border: usize) {
+        self.fill_border(Cell::Dead, border);
+    }
+
+    /// Sets every cell within `thickness` cells of the edge of the world to `state`, such as to build a wall around an
+    /// arena. A thickness of at least half the world's width or height fills the whole world.
+    pub fn fill_border(&mut self, state: Cell, thickness: usize) {
+        for y in 0..self.height {
+            let near_edge = y < thickness || self.height - y <= thickness;
+
+            for x in 0..self.width {
+                if near_edge || x < thickness || self.width - x <= thickness {
+                    self.cells[y * self.width + x] = state;
+                }
+            }
+        }
+    }
+
+    /// Checks whether every cell in the world is dead. This is true for a world with no cells at all.
+    pub fn is_empty(&self) -> bool {
+        !self.cells.iter().any(|cell| cell.alive())
+    }
+
+    /// Checks whether every cell in the world is alive. Like `is_empty`, this is also true for a world with no cells.
+    pub fn is_full(&self) -> bool {
+        self.cells.iter().all(|cell| cell.alive())
+    }
+
+    /// Counts the live cells in the world.
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|cell| cell.alive()).count()
+    }
+
+    /// The fraction of the world's cells that are alive, between 0 and 1. This is 0 for a world with no cells at all.
+    pub fn density(&self) -> f64 {
+        if self.cells.is_empty() {
+            0.0
+        } else {
+            self.population() as f64 / self.cells.len() as f64
+        }
+    }
+
+    /// Constructs a new `World` from rows of cells, where `true` is alive. Every row must be the same length. No rows at
+    /// all gives an empty world with a width and height of 0.
+    pub fn from_bool_grid(rows: &[Vec<bool>]) -> Result<Self, WorldError> {
+        let width = rows.first().map_or(0, Vec::len);
+        let mut world = World::new(width, rows.len());
+
+        for (y, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(WorldError::RaggedRows {
+                    row: y,
+                    expected: width,
+                    found: row.len(),
+                });
+            }
+
+            for (x, &alive) in row.iter().enumerate() {
+                world.cells[y * width + x] = Cell::from_bool(alive);
+            }
+        }
+
+        Ok(world)
+    }
+
+    /// Constructs a new `World` out of cells that are already laid out in row-major order, taking ownership of them
+    /// rather than copying them one at a time. There must be exactly `width * height` cells.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<Cell>) -> Result<Self, WorldError> {
+        if width.checked_mul(height) != Some(cells.len()) {
+            return Err(WorldError::WrongCellCount {
+                width,
+                height,
+                found: cells.len(),
+            });
+        }
+
+        Ok(World {
+            width,
+            height,
+            cells: cells.into_boxed_slice(),
+            edge_behavior: EdgeBehavior::default(),
+            metadata: Metadata::default(),
+            rule: Rule::default(),
+        })
+    }
+
+    /// Converts the world into rows of cells, where `true` is alive. Markers are treated as dead cells.
+    pub fn to_bool_grid(&self) -> Vec<Vec<bool>> {
+        (0..self.height)
+            .map(|y| {
+                self.row(y)
+                    .unwrap()
+                    .iter()
+                    .map(|cell| cell.alive())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Returns an iterator over the positions of every live cell, in row-major order.
+    pub fn live_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.iter()
+            .filter(|cell| cell.state.alive())
+            .map(|cell| cell.position)
+    }
+
+    /// Returns an iterator over the cells on the straight line from `from` to `to`, including both ends, along with
+    /// their positions. The line is worked out with Bresenham's algorithm, so every step moves to one of the eight
+    /// neighboring cells. Parts of the line that are outside of the world are skipped, and the line never wraps around,
+    /// whatever the world's edge behavior is.
+    pub fn cells_along(
+        &self,
+        from: (usize, usize),
+        to: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize, Cell)> + '_ {
+        let (mut x, mut y) = (from.0 as isize, from.1 as isize);
+        let (end_x, end_y) = (to.0 as isize, to.1 as isize);
+        let (dx, dy) = ((end_x - x).abs(), -(end_y - y).abs());
+        let (step_x, step_y) = ((end_x - x).signum(), (end_y - y).signum());
+        let mut error = dx + dy;
+        let mut done = false;
+
+        let points = std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+
+            let point = (x as usize, y as usize);
+            done = (x, y) == (end_x, end_y);
+
+            // The error term keeps track of how far the line has drifted from the ideal one, and decides which of the
+            // two axes to step along next (or both, for a diagonal step).
+            let doubled = 2 * error;
+
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+
+            Some(point)
+        });
+
+        points.filter_map(|(x, y)| self.get((x, y)).map(|cell| (x, y, cell)))
+    }
+
+    /// Constructs a new `World` with the same dimensions, where each cell is replaced by the result of calling `f` with
+    /// its position and current state. The new cells are written into a single fresh buffer.
+    pub fn map(&self, f: impl Fn((usize, usize), Cell) -> Cell) -> Self {
+        let cells = self
+            .iter()
+            .map(|LocatedCell { position, state }| f(position, state))
+            .collect();
+
+        World {
+            width: self.width,
+            height: self.height,
+            cells,
+            edge_behavior: self.edge_behavior,
+            metadata: self.metadata.clone(),
+            rule: self.rule,
+        }
+    }
+
+    /// Constructs a new `World` with every cell flipped. Inverting a world twice gives back the original world.
+    pub fn inverted(&self) -> Self {
+        self.map(|_, cell| cell.flipped())
+    }
+
+    /// Shrinks the world by a factor of `scale` in each direction, so that each cell of the result stands for a `scale`
+    /// by `scale` block of this world. `mode` decides which blocks are alive, and the blocks along the right and bottom
+    /// edges are cut short if the size isn't a multiple of `scale`. The edge behavior is kept. Panics if `scale` is 0.
+    pub fn downsampled(&self, scale: usize, mode: Downsample) -> World {
+        assert!(scale > 0, "can't downsample a world by a factor of 0");
+
+        let (width, height) = (self.width.div_ceil(scale), self.height.div_ceil(scale));
+        let mut counts = vec![0; width * height];
+
+        for (x, y) in self.live_cells() {
+            counts[(y / scale) * width + x / scale] += 1;
+        }
+
+        let mut map = World::new(width, height);
+        map.edge_behavior = self.edge_behavior;
+        map.rule = self.rule;
+
+        for (index, &count) in counts.iter().enumerate() {
+            let (x, y) = (index % width, index / width);
+            // Blocks along the edges can have fewer cells than the rest.
+            let cells = scale.min(self.width - x * scale) * scale.min(self.height - y * scale);
+
+            map.cells[index] = Cell::from_bool(match mode {
+                Downsample::Any => count > 0,
+                Downsample::Majority => count * 2 > cells,
+            });
+        }
+
+        map
+    }
+
     pub fn get(&self, position: impl WorldIndex) -> Option<Cell> {
         let index = position.to_index(self)?;
         // SAFETY: `to_index` has already checked that this is a valid index.
@@ -43,44 +467,577 @@ impl World {
         unsafe { Some(self.cells.get_unchecked_mut(index)) }
     }
 
-    pub fn has_live_neighbor(&self, (x, y): (usize, usize), position: Position) -> bool {
-        let (x_offset, y_offset) = position.offset();
-        let (new_x, new_y) = ((x as isize + x_offset), (y as isize + y_offset));
+    /// Sets the cell at `position`, returning whether the position was inside the world. Nothing happens if it wasn't.
+    pub fn set(&mut self, position: impl WorldIndex, cell: Cell) -> bool {
+        self.get_mut(position)
+            .map(|target| *target = cell)
+            .is_some()
+    }
 
-        if new_x < 0 || new_y < 0 {
-            false
-        } else {
-            self.get((new_x as usize, new_y as usize))
-                .map_or(false, |cell| cell.alive())
+    /// Flips the cell at `position`, returning whether the position was inside the world. Nothing happens if it wasn't.
+    pub fn toggle(&mut self, position: impl WorldIndex) -> bool {
+        self.get_mut(position).map(Cell::flip).is_some()
+    }
+
+    /// Returns the cells in row `y` as a contiguous slice, or `None` if the row is outside of the world.
+    pub fn row(&self, y: usize) -> Option<&[Cell]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    /// Returns an iterator over the cells in column `x`, from top to bottom. This is empty if the column is outside of
+    /// the world.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = Cell> + '_ {
+        let height = if x < self.width { self.height } else { 0 };
+        (0..height).map(move |y| self.cells[y * self.width + x])
+    }
+
+    /// Returns the coordinates of the neighbor in the given direction, taking the world's edge behavior into account.
+    /// This is `None` if the neighbor would be outside of the world.
+    pub fn neighbor_coords(
+        &self,
+        (x, y): (usize, usize),
+        position: Position,
+    ) -> Option<(usize, usize)> {
+        if x >= self.width || y >= self.height {
+            return None;
         }
+
+        let (x_offset, y_offset) = position.offset();
+        let column = self.column_span(x)[(x_offset + 1) as usize]?;
+        let row = self.row_span(y)[(y_offset + 1) as usize]?;
+
+        Some((column, row))
+    }
+
+    pub fn has_live_neighbor(&self, (x, y): (usize, usize), position: Position) -> bool {
+        self.neighbor_coords((x, y), position)
+            .and_then(|coords| self.get(coords))
+            .is_some_and(|cell| cell.alive())
+    }
+
+    /// Yields each of the eight neighbors of a cell along with the direction it's in. The cell is `None` if the neighbor
+    /// would be outside of the world, and otherwise follows the world's edge behavior, so wrapped neighbors come from
+    /// the opposite edge.
+    pub fn neighbors(
+        &self,
+        (x, y): (usize, usize),
+    ) -> impl Iterator<Item = (Position, Option<Cell>)> + '_ {
+        Position::all().into_iter().map(move |position| {
+            let cell = self
+                .neighbor_coords((x, y), position)
+                .and_then(|coords| self.get(coords));
+
+            (position, cell)
+        })
+    }
+
+    /// Returns which of a cell's neighbors are alive as a bitmask, where bit `i` (counting from the least significant
+    /// bit) is set if the neighbor in the direction `Position::all()[i]` is alive. So bit 0 is the top left neighbor,
+    /// and bit 7 is the bottom right one. Neighbors outside of the world are never set.
+    pub fn neighbor_mask(&self, (x, y): (usize, usize)) -> u8 {
+        self.neighbors((x, y))
+            .enumerate()
+            .filter(|(_, (_, cell))| cell.is_some_and(|cell| cell.alive()))
+            .fold(0, |mask, (bit, _)| mask | 1 << bit)
     }
 
     pub fn live_neighbors(&self, (x, y): (usize, usize)) -> usize {
-        Position::all()
-            .into_iter()
-            .map(|position| self.has_live_neighbor((x, y), position) as usize)
+        self.neighbor_mask((x, y)).count_ones() as usize
+    }
+
+    /// Counts the live neighbors of a cell in only the given directions, which is useful for experimenting with rules
+    /// that aren't symmetrical. A direction that's given more than once is still only counted once.
+    pub fn live_neighbors_masked(&self, (x, y): (usize, usize), mask: &[Position]) -> usize {
+        self.neighbors((x, y))
+            .filter(|(position, _)| mask.contains(position))
+            .filter(|(_, cell)| cell.is_some_and(|cell| cell.alive()))
+            .count()
+    }
+
+    /// Computes a weighted sum over the 3x3 neighborhood of every cell in the world, in row-major order. Live cells count
+    /// as 1 and everything else counts as 0, and `kernel[row][column]` is the weight for the cell that's `row - 1` rows
+    /// and `column - 1` columns away, so `kernel[1][1]` is the weight for the cell itself.
+    ///
+    /// Neighbors follow the world's edge behavior like they do in `live_neighbors`, so neighbors past a dead edge count
+    /// as 0, and a neighbor that wraps around onto a cell that's already part of the neighborhood isn't counted again.
+    /// That means a kernel of all ones apart from a 0 in the middle gives the same counts as `neighbor_counts`.
+    pub fn convolve3x3(&self, kernel: [[i32; 3]; 3]) -> Vec<i32> {
+        self.iter()
+            .map(|LocatedCell { position, state }| {
+                let neighbors: i32 = Position::all()
+                    .into_iter()
+                    .filter(|&direction| self.has_live_neighbor(position, direction))
+                    .map(|direction| {
+                        let (x, y) = direction.offset();
+                        kernel[(y + 1) as usize][(x + 1) as usize]
+                    })
+                    .sum();
+
+                neighbors + kernel[1][1] * state.alive() as i32
+            })
+            .collect()
+    }
+
+    /// Counts the live neighbors of every cell in the world, in row-major order.
+    pub fn neighbor_counts(&self) -> Vec<usize> {
+        self.iter()
+            .map(|LocatedCell { position, .. }| self.live_neighbors(position))
+            .collect()
+    }
+
+    /// Counts how many cells have each possible number of live neighbors, so that index `n` holds the number of cells
+    /// with exactly `n` live neighbors.
+    pub fn neighbor_histogram(&self) -> [usize; 9] {
+        let mut histogram = [0; 9];
+
+        for LocatedCell { position, .. } in self.iter() {
+            histogram[self.live_neighbors(position)] += 1;
+        }
+
+        histogram
+    }
+
+    /// Counts the separate clusters of live cells in the world, where live cells that touch (diagonally or not) are part
+    /// of the same cluster, so a live cell with no live neighbors is a cluster of its own. Neighbors follow the world's
+    /// edge behavior, so a pattern that's split across an edge that wraps around only counts once.
+    pub fn cluster_count(&self) -> usize {
+        let mut visited = vec![false; self.cells.len()];
+        let mut pending = Vec::new();
+        let mut clusters = 0;
+
+        for start in 0..self.cells.len() {
+            if visited[start] || !self.cells[start].alive() {
+                continue;
+            }
+
+            // Each cluster is flood filled from the first of its cells that's found, using a stack rather than
+            // recursion so that big clusters can't overflow the call stack.
+            clusters += 1;
+            visited[start] = true;
+            pending.push((start % self.width, start / self.width));
+
+            while let Some(position) = pending.pop() {
+                for direction in Position::all() {
+                    let Some((x, y)) = self.neighbor_coords(position, direction) else {
+                        continue;
+                    };
+
+                    let index = y * self.width + x;
+
+                    if !visited[index] && self.cells[index].alive() {
+                        visited[index] = true;
+                        pending.push((x, y));
+                    }
+                }
+            }
+        }
+
+        clusters
+    }
+
+    /// Counts the live cells in the inclusive rectangle spanning `top_left` to `bottom_right`. Coordinates outside of the
+    /// world are clamped to its edges, and only the cells inside the region are visited.
+    pub fn region_population(
+        &self,
+        (left, top): (usize, usize),
+        (right, bottom): (usize, usize),
+    ) -> usize {
+        if self.width == 0 || self.height == 0 {
+            return 0;
+        }
+
+        let (right, bottom) = (right.min(self.width - 1), bottom.min(self.height - 1));
+
+        if left > right || top > bottom {
+            return 0;
+        }
+
+        (top..=bottom)
+            .map(|y| {
+                let start = y * self.width;
+                self.cells[start + left..=start + right]
+                    .iter()
+                    .filter(|cell| cell.alive())
+                    .count()
+            })
             .sum()
     }
 
-    pub fn tick(self) -> Self {
-        let mut new = self.clone();
+    /// Returns the inclusive top-left and bottom-right corners of the smallest rectangle containing every live cell, or
+    /// `None` if there are no live cells.
+    pub fn bounding_box(&self) -> Option<((usize, usize), (usize, usize))> {
+        let mut live = self.live_cells();
+
+        let first = live.next()?;
+        let corners = live.fold((first, first), |((left, top), (right, bottom)), (x, y)| {
+            ((left.min(x), top.min(y)), (right.max(x), bottom.max(y)))
+        });
+
+        Some(corners)
+    }
+
+    /// Constructs a new `World` containing just the bounding box of this world's live cells. If there are no live
+    /// cells, this is an empty world with a width and height of 0.
+    pub fn trimmed(&self) -> Self {
+        let mut trimmed = match self.bounding_box() {
+            Some(((left, top), (right, bottom))) => {
+                let mut trimmed = World::new(right - left + 1, bottom - top + 1);
+
+                for y in top..=bottom {
+                    let row = &self.row(y).unwrap()[left..=right];
+                    let start = (y - top) * trimmed.width;
+                    trimmed.cells[start..start + row.len()].copy_from_slice(row);
+                }
+
+                trimmed
+            }
+            None => World::new(0, 0),
+        };
+
+        trimmed.edge_behavior = self.edge_behavior;
+        trimmed.rule = self.rule;
+        trimmed
+    }
+
+    /// Copies the `width` by `height` region with its top-left corner at `top_left` into a new world, keeping the edge
+    /// behavior. With `Overflow::Error` a region that goes past the edges of this world is an error, and with
+    /// `Overflow::Drop` it's clipped to the edges instead. A region with no cells in it gives a world with a width and
+    /// height of 0.
+    pub fn subgrid(
+        &self,
+        (left, top): (usize, usize),
+        width: usize,
+        height: usize,
+        overflow: Overflow,
+    ) -> Result<World, WorldError> {
+        let clipped = (
+            width.min(self.width.saturating_sub(left)),
+            height.min(self.height.saturating_sub(top)),
+        );
+
+        if clipped != (width, height) && width > 0 && height > 0 && overflow == Overflow::Error {
+            return Err(WorldError::OutOfBounds {
+                top_left: (left, top),
+                width,
+                height,
+            });
+        }
+
+        let (width, height) = match clipped {
+            (0, _) | (_, 0) => (0, 0),
+            clipped => clipped,
+        };
+
+        let mut subgrid = World::new(width, height);
+
+        for y in 0..subgrid.height {
+            let row = &self.row(top + y).unwrap()[left..left + width];
+            subgrid.cells[y * width..(y + 1) * width].copy_from_slice(row);
+        }
+
+        subgrid.edge_behavior = self.edge_behavior;
+        subgrid.rule = self.rule;
+        Ok(subgrid)
+    }
+
+    /// Sets every cell at the given positions to `cell`, ignoring any positions outside of the world. Unlike flipping
+    /// each cell in turn, this gives the same result even if a position is given more than once, which makes it the
+    /// right choice for anything that changes several cells at once.
+    pub fn set_region(&mut self, positions: impl IntoIterator<Item = (usize, usize)>, cell: Cell) {
+        for position in positions {
+            if let Some(target) = self.get_mut(position) {
+                *target = cell;
+            }
+        }
+    }
+
+    /// Copies the live cells of `pattern` into this world, with the top-left corner of `pattern` placed at `top_left`.
+    /// Dead cells in `pattern` leave this world as it was, and live cells that would land outside of this world are left
+    /// out. Returns whether every live cell fit.
+    pub fn overlay(&mut self, pattern: &World, (left, top): (usize, usize)) -> bool {
+        let mut fit = true;
+
+        for (x, y) in pattern.live_cells() {
+            match self.get_mut((left + x, top + y)) {
+                Some(cell) => *cell = Cell::Alive,
+                None => fit = false,
+            }
+        }
+
+        fit
+    }
+
+    /// Counts the cells whose state differs between this world and `other`.
+    ///
+    /// # Panics
+    /// Panics if `other` isn't the same size as this world.
+    pub fn changed_cells(&self, other: &World) -> usize {
+        assert!(
+            other.width == self.width && other.height == self.height,
+            "worlds must be the same size"
+        );
+
+        self.cells
+            .iter()
+            .zip(other.cells.iter())
+            .filter(|(a, b)| a != b)
+            .count()
+    }
+
+    /// Constructs a new `World` rotated a quarter turn clockwise, so that its width and height are swapped.
+    pub fn rotated(&self) -> Self {
+        let mut rotated = World::new(self.height, self.width);
 
         for LocatedCell { position, state } in self.iter() {
-            let neighbors = self.live_neighbors(position);
-            let new_state = match state {
-                Cell::Alive if (2..=3).contains(&neighbors) => Cell::Alive,
-                Cell::Alive => Cell::Dead,
-                Cell::Dead if neighbors == 3 => Cell::Alive,
-                Cell::Dead => Cell::Dead,
-            };
+            let (x, y) = position;
+            rotated[(self.height - 1 - y, x)] = state;
+        }
+
+        rotated.edge_behavior = self.edge_behavior;
+        rotated.metadata.clone_from(&self.metadata);
+        rotated.rule = self.rule;
+        rotated
+    }
+
+    /// Constructs a new `World` flipped left to right.
+    pub fn mirrored(&self) -> Self {
+        self.map(|(x, y), _| self[(self.width - 1 - x, y)])
+    }
+
+    /// Picks one representative out of the eight rotations and reflections of the world's live cells, so that patterns
+    /// which are rotations or reflections of each other all give exactly the same world. The pattern is trimmed first,
+    /// markers are treated as dead, and the result has the default edge behavior and rule and no metadata, which makes
+    /// it suitable as a key for deduplicating patterns. The representative is the smallest one, going by the ordering of
+    /// worlds.
+    pub fn canonical(&self) -> Self {
+        let mut pattern = self.map(|_, cell| Cell::from_bool(cell.alive())).trimmed();
+        pattern.edge_behavior = EdgeBehavior::default();
+        pattern.metadata = Metadata::default();
+        pattern.rule = Rule::default();
+
+        let mirrored = pattern.mirrored();
+        let mut transforms = Vec::with_capacity(8);
+
+        for start in [pattern, mirrored] {
+            let mut current = start;
+
+            for _ in 0..4 {
+                let next = current.rotated();
+                transforms.push(current);
+                current = next;
+            }
+        }
+
+        transforms.into_iter().min().unwrap()
+    }
+
+    /// Checks whether two worlds contain the same pattern of live cells, regardless of where in the world the pattern
+    /// is, or how big the worlds are.
+    pub fn same_pattern(&self, other: &World) -> bool {
+        let (left, right) = (self.trimmed(), other.trimmed());
+        left.width == right.width && left.height == right.height && left.cells == right.cells
+    }
+
+    /// Computes the next generation of the world under its own rule.
+    pub fn tick(self) -> Self {
+        let rule = self.rule;
+        self.tick_with(&rule)
+    }
+
+    /// Computes the next generation of the world under the given rule.
+    pub fn tick_with(self, rule: &Rule) -> Self {
+        self.next_generation(rule)
+    }
+
+    /// Computes the world `generations` generations from now under the given rule. Only two buffers are used, no matter
+    /// how many generations are computed.
+    pub fn tick_n(self, generations: usize, rule: &Rule) -> Self {
+        let mut current = self;
+        let mut next = World::new(current.width, current.height);
 
-            // SAFETY: `LocatedCell` guarantees that `position` is a valid position in the world.
-            *new.get_mut(position).unwrap() = new_state;
+        for _ in 0..generations {
+            current.tick_into_with(rule, &mut next);
+            std::mem::swap(&mut current, &mut next);
         }
 
+        current
+    }
+
+    /// Replaces the world with its next generation under its own rule, returning whether any cell changed. This is
+    /// worked out while ticking, so there's no need to compare the two generations afterwards. A `false` means the
+    /// world is a still life (or empty) and will never change again, but oscillators with a period of 2 or more always
+    /// return `true`, since some of their cells change every generation. Use `run_until_stable` to catch those too.
+    ///
+    /// The next generation is ticked into a buffer that's kept between steps (one per thread), so stepping the same
+    /// world over and over doesn't allocate.
+    pub fn step(&mut self) -> bool {
+        STEP_BUFFER.with(|buffer| {
+            let mut next = buffer.take();
+            let mut changed = false;
+
+            self.tick_into_observed(&self.rule, &mut next, |_| changed = true);
+            std::mem::swap(self, &mut next);
+            buffer.replace(next);
+
+            changed
+        })
+    }
+
+    /// Computes the next generation of the world under its own rule, calling `on_event` once for every cell that's born
+    /// or dies along the way.
+    pub fn tick_observe(&self, on_event: impl FnMut(CellEvent)) -> Self {
+        self.tick_observe_with(&self.rule, on_event)
+    }
+
+    /// Computes the next generation of the world under the given rule, calling `on_event` once for every cell that's
+    /// born or dies along the way. The events are reported in row-major order as each cell is worked out, so there's no
+    /// need for a separate pass comparing the two generations.
+    pub fn tick_observe_with(&self, rule: &Rule, on_event: impl FnMut(CellEvent)) -> Self {
+        let mut new = World::new(self.width, self.height);
+        self.tick_into_observed(rule, &mut new, on_event);
         new
     }
 
+    /// Returns an infinite iterator over the following generations of the world under its own rule, starting with
+    /// generation 1. Each generation is computed lazily, ticking back and forth between two buffers that the iterator
+    /// keeps. The worlds it yields are copies of those buffers, so use `Generations::advance` to step through without
+    /// allocating at all.
+    pub fn generations(self) -> Generations {
+        Generations {
+            spare: World::new(self.width, self.height),
+            current: self,
+        }
+    }
+
+    /// Computes the next generation of the world into a fresh buffer, leaving this one untouched.
+    fn next_generation(&self, rule: &Rule) -> Self {
+        let mut new = World::new(self.width, self.height);
+        self.tick_into_with(rule, &mut new);
+        new
+    }
+
+    /// Computes the next generation of the world under its own rule, writing it into `out` without allocating. The prior
+    /// contents of `out` are fully overwritten, including its edge behavior, metadata, and rule. If `out` isn't the same
+    /// size as this world, it's resized to match first, which is the only time that this allocates.
+    pub fn tick_into(&self, out: &mut World) {
+        self.tick_into_with(&self.rule, out)
+    }
+
+    /// Computes the next generation of the world under the given rule, writing it into `out` without allocating. The
+    /// prior contents of `out` are fully overwritten, including its edge behavior, metadata, and rule. If `out` isn't the
+    /// same size as this world, it's resized to match first, which is the only time that this allocates.
+    pub fn tick_into_with(&self, rule: &Rule, out: &mut World) {
+        self.tick_into_observed(rule, out, |_| {});
+    }
+
+    /// The shared implementation of `tick_into_with` and `tick_observe_with`, calling `on_event` for every cell that
+    /// changes.
+    fn tick_into_observed(
+        &self,
+        rule: &Rule,
+        out: &mut World,
+        mut on_event: impl FnMut(CellEvent),
+    ) {
+        if out.width != self.width || out.height != self.height {
+            *out = World::new(self.width, self.height);
+        }
+
+        // The neighboring columns of each x coordinate are the same for every row, so they're only worked out once.
+        let columns: Vec<_> = (0..self.width).map(|x| self.column_span(x)).collect();
+
+        for y in 0..self.height {
+            // Each row is looked at as a contiguous slice, rather than going through `get` for every neighbor.
+            let rows = self
+                .row_span(y)
+                .map(|row| row.and_then(|row| self.row(row)));
+
+            for (x, span) in columns.iter().enumerate() {
+                let mut neighbors = 0;
+
+                for (row_offset, row) in rows.iter().enumerate() {
+                    let Some(row) = row else { continue };
+
+                    for (column_offset, column) in span.iter().enumerate() {
+                        // The cell itself isn't one of its own neighbors.
+                        if (row_offset, column_offset) == (1, 1) {
+                            continue;
+                        }
+
+                        if let Some(column) = column {
+                            neighbors += row[*column].alive() as usize;
+                        }
+                    }
+                }
+
+                let index = y * self.width + x;
+                let cell = self.cells[index];
+
+                let next = match cell {
+                    Cell::Marker => Cell::Marker,
+                    _ => Cell::from_bool(rule::next_state(cell.alive(), neighbors, rule)),
+                };
+
+                match (cell.alive(), next.alive()) {
+                    (false, true) => on_event(CellEvent::Born((x, y))),
+                    (true, false) => on_event(CellEvent::Died((x, y))),
+                    _ => {}
+                }
+
+                out.cells[index] = next;
+            }
+        }
+
+        out.edge_behavior = self.edge_behavior;
+        out.metadata.clone_from(&self.metadata);
+        out.rule = self.rule;
+    }
+
+    /// The columns to the left of, at, and to the right of `x`, following the world's edge behavior.
+    fn column_span(&self, x: usize) -> [Option<usize>; 3] {
+        neighbor_span(x, self.width, self.edge_behavior.wraps_horizontally())
+    }
+
+    /// The rows above, at, and below `y`, following the world's edge behavior.
+    fn row_span(&self, y: usize) -> [Option<usize>; 3] {
+        neighbor_span(y, self.height, self.edge_behavior.wraps_vertically())
+    }
+
+    /// Get the world's edge behavior.
+    pub fn edge_behavior(&self) -> EdgeBehavior {
+        self.edge_behavior
+    }
+
+    /// Set the world's edge behavior. This takes effect from the next call to `tick`.
+    pub fn set_edge_behavior(&mut self, edge_behavior: EdgeBehavior) {
+        self.edge_behavior = edge_behavior;
+    }
+
+    /// Get the rule that `tick` simulates the world under.
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    /// Set the rule that `tick` simulates the world under.
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+    }
+
+    /// Gives the world a rule for `tick` to simulate it under, for building worlds in one expression.
+    pub fn with_rule(mut self, rule: Rule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Get a reference to the world's metadata.
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    /// Get a mutable reference to the world's metadata.
+    pub fn metadata_mut(&mut self) -> &mut Metadata {
+        &mut self.metadata
+    }
+
     /// Get a reference to the world's width.
     pub fn width(&self) -> usize {
         self.width
@@ -92,11 +1049,48 @@ impl World {
     }
 }
 
+/// Returns the indices before, at, and after `index` along an axis of the given length, either wrapping around at the
+/// ends or not. Both `neighbor_coords` and the tick loop are built on this, so that they always agree.
+fn neighbor_span(index: usize, length: usize, wrap: bool) -> [Option<usize>; 3] {
+    if !wrap {
+        return [
+            index.checked_sub(1),
+            Some(index),
+            (index + 1 < length).then_some(index + 1),
+        ];
+    }
+
+    // On axes shorter than 3 cells, both sides would wrap around to the same cell (or to the cell itself), so the
+    // duplicates are left out rather than being counted more than once.
+    match length {
+        1 => [None, Some(index), None],
+        2 => [Some(1 - index), Some(index), None],
+        _ => [
+            Some((index + length - 1) % length),
+            Some(index),
+            Some((index + 1) % length),
+        ],
+    }
+}
+
+/// Draws the world with `@` for live cells and `.` for dead cells. The alternate form (`{:#}`) uses
+/// `RenderMode::Minimal` instead.
 impl Display for World {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mode = if f.alternate() {
+            RenderMode::Minimal
+        } else {
+            RenderMode::Classic
+        };
+
+        // `chunks` panics on a chunk size of 0, and there's nothing to draw anyway.
+        if self.width == 0 {
+            return Ok(());
+        }
+
         for (row_index, row) in self.cells.chunks(self.width).enumerate() {
             for cell in row {
-                f.write_char(cell.block())?;
+                f.write_char(cell.glyph(mode))?;
             }
 
             // We don't want to leave a trailing newline.
@@ -118,7 +1112,7 @@ impl WorldIndex for (usize, usize) {
     fn to_index(&self, world: &World) -> Option<usize> {
         let (x, y) = *self;
         if x >= world.width() || y >= world.height {
-            None 
+            None
         } else {
             (y * world.width + x).to_index(world)
         }
@@ -128,7 +1122,7 @@ impl WorldIndex for (usize, usize) {
 impl WorldIndex for usize {
     fn to_index(&self, world: &World) -> Option<usize> {
         let index = *self;
-        (index < world.width * world.height).then(|| index)
+        (index < world.width * world.height).then_some(index)
     }
 }
 
@@ -150,6 +1144,29 @@ impl<T: WorldIndex> IndexMut<T> for World {
     }
 }
 
+/// An infinite iterator over the generations of a world, made by `World::generations`.
+pub struct Generations {
+    current: World,
+    spare: World,
+}
+
+impl Generations {
+    /// Moves on to the next generation and returns it, without allocating.
+    pub fn advance(&mut self) -> &World {
+        self.current.tick_into(&mut self.spare);
+        std::mem::swap(&mut self.current, &mut self.spare);
+        &self.current
+    }
+}
+
+impl Iterator for Generations {
+    type Item = World;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.advance().clone())
+    }
+}
+
 pub struct WorldIterator<'a> {
     world: &'a World,
     index: usize,
@@ -165,16 +1182,13 @@ impl<'a> Iterator for WorldIterator<'a> {
     type Item = LocatedCell;
 
     fn next(&mut self) -> Option<Self::Item> {
+        // This is checked first, so that we never divide by a width of 0.
+        let state = self.world.get(self.index)?;
         let position = (self.index % self.world.width, self.index / self.world.width);
 
-        let result = self
-            .world
-            .get(self.index)
-            .map(|state| LocatedCell { position, state })?;
-
         self.index += 1;
 
-        Some(result)
+        Some(LocatedCell { position, state })
     }
 }
 
@@ -187,3 +1201,561 @@ impl<'a> IntoIterator for &'a World {
         WorldIterator::new(self)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{format::Format, random::Rng};
+
+    const EDGE_BEHAVIORS: [EdgeBehavior; 4] = [
+        EdgeBehavior::Dead,
+        EdgeBehavior::Wrap,
+        EdgeBehavior::WrapHorizontal,
+        EdgeBehavior::WrapVertical,
+    ];
+
+    /// Builds a world out of plaintext rows, where `O` is a live cell and `.` is a dead one.
+    fn world(rows: &str) -> World {
+        Format::Plaintext.parse(rows).unwrap()
+    }
+
+    /// The straightforward way of computing the next generation, one cell at a time through `live_neighbors`, which is
+    /// what `tick` did before it was rewritten to walk rows as slices.
+    fn reference_tick(world: &World) -> World {
+        world.map(|position, cell| match cell {
+            Cell::Marker => Cell::Marker,
+            _ => Cell::from_bool(rule::next_state(
+                cell.alive(),
+                world.live_neighbors(position),
+                &world.rule(),
+            )),
+        })
+    }
+
+    #[test]
+    fn tick_matches_the_reference_implementation() {
+        let sizes = [(1, 1), (1, 5), (2, 2), (5, 1), (3, 3), (7, 4), (32, 24)];
+
+        for (seed, &(width, height)) in sizes.iter().enumerate() {
+            for edges in EDGE_BEHAVIORS {
+                let mut world = World::random(width, height, 0.4, &mut Rng::new(seed as u64));
+                world.set_edge_behavior(edges);
+                world[(0, 0)] = Cell::Marker;
+
+                for _ in 0..8 {
+                    let expected = reference_tick(&world);
+                    world = world.tick();
+                    assert_eq!(
+                        world, expected,
+                        "{}x{} with {:?} edges",
+                        width, height, edges
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn empty_worlds_display_as_nothing() {
+        assert_eq!(World::new(0, 0).to_string(), "");
+        assert_eq!(World::new(0, 3).to_string(), "");
+    }
+
+    #[test]
+    fn region_population_counts_inside_the_region() {
+        let world = world("OO..\nO.O.\n...O");
+
+        assert_eq!(world.region_population((0, 0), (1, 1)), 3);
+        assert_eq!(world.region_population((1, 1), (2, 2)), 1);
+        assert_eq!(world.region_population((3, 2), (3, 2)), 1);
+        assert_eq!(world.region_population((0, 0), (3, 2)), world.population());
+    }
+
+    #[test]
+    fn region_population_clamps_regions_straddling_the_edges() {
+        let world = world("OO..\nO.O.\n...O");
+
+        assert_eq!(world.region_population((2, 1), (10, 10)), 2);
+        assert_eq!(world.region_population((0, 0), (usize::MAX, usize::MAX)), 5);
+        assert_eq!(world.region_population((4, 0), (10, 10)), 0);
+        assert_eq!(world.region_population((0, 3), (10, 10)), 0);
+        assert_eq!(world.region_population((2, 2), (1, 1)), 0);
+        assert_eq!(World::new(0, 0).region_population((0, 0), (5, 5)), 0);
+    }
+
+    #[test]
+    fn rows_and_columns_stop_at_the_edges() {
+        let world = world("O..\n.OO");
+
+        assert_eq!(
+            world.row(0),
+            Some(&[Cell::Alive, Cell::Dead, Cell::Dead][..])
+        );
+        assert_eq!(
+            world.row(1),
+            Some(&[Cell::Dead, Cell::Alive, Cell::Alive][..])
+        );
+        assert_eq!(world.row(2), None);
+
+        assert_eq!(
+            world.column(0).collect::<Vec<_>>(),
+            [Cell::Alive, Cell::Dead]
+        );
+        assert_eq!(
+            world.column(2).collect::<Vec<_>>(),
+            [Cell::Dead, Cell::Alive]
+        );
+        assert_eq!(world.column(3).count(), 0);
+        assert_eq!(World::new(0, 0).row(0), None);
+    }
+
+    #[test]
+    fn small_tori_count_each_neighbor_once() {
+        // On a full world, every cell's count is the number of distinct other cells around it.
+        for (width, height, expected) in [(1, 1, 0), (1, 2, 1), (2, 1, 1), (2, 2, 3), (3, 3, 8)] {
+            let mut world = World::new(width, height);
+            world.set_edge_behavior(EdgeBehavior::Wrap);
+            world.fill_border(Cell::Alive, 2);
+
+            for LocatedCell { position, .. } in world.iter() {
+                assert_eq!(
+                    world.live_neighbors(position),
+                    expected,
+                    "{:?} on a {}x{} torus",
+                    position,
+                    width,
+                    height
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn small_tori_never_count_the_cell_itself() {
+        for (width, height) in [(1, 1), (1, 2), (2, 2), (3, 3)] {
+            let mut world = World::new(width, height);
+            world.set_edge_behavior(EdgeBehavior::Wrap);
+            world[(0, 0)] = Cell::Alive;
+
+            assert_eq!(
+                world.live_neighbors((0, 0)),
+                0,
+                "on a {}x{} torus",
+                width,
+                height
+            );
+        }
+    }
+
+    #[test]
+    fn neighbor_histogram_of_a_block() {
+        let block = world("....\n.OO.\n.OO.\n....");
+
+        // The block's own cells have 3 neighbors each, the cells beside it have 2, and the corners have 1.
+        assert_eq!(block.neighbor_histogram(), [0, 4, 8, 4, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn empty_and_full_worlds() {
+        assert!(world("...\n...").is_empty());
+        assert!(!world("...\n...").is_full());
+
+        assert!(world("OOO\nOOO").is_full());
+        assert!(!world("OOO\nOOO").is_empty());
+
+        assert!(!world(".O.\n...").is_empty());
+        assert!(!world(".O.\n...").is_full());
+
+        assert!(World::new(0, 0).is_empty());
+        assert!(World::new(0, 0).is_full());
+    }
+
+    /// The neighbors of a cell in the order `Position::all()` gives them, leaving out the positions themselves.
+    fn neighbor_cells(world: &World, position: (usize, usize)) -> Vec<Option<Cell>> {
+        world.neighbors(position).map(|(_, cell)| cell).collect()
+    }
+
+    #[test]
+    fn neighbors_of_interior_edge_and_corner_cells() {
+        let (alive, dead) = (Some(Cell::Alive), Some(Cell::Dead));
+        let mut world = world("O.O\n.O.\nOOO");
+
+        let directions: Vec<_> = world
+            .neighbors((1, 1))
+            .map(|(direction, _)| direction)
+            .collect();
+        assert_eq!(directions, Position::all());
+
+        assert_eq!(
+            neighbor_cells(&world, (1, 1)),
+            [alive, dead, alive, dead, dead, alive, alive, alive]
+        );
+        assert_eq!(
+            neighbor_cells(&world, (1, 0)),
+            [None, None, None, alive, alive, dead, alive, dead]
+        );
+        assert_eq!(
+            neighbor_cells(&world, (0, 0)),
+            [None, None, None, None, dead, None, dead, alive]
+        );
+
+        world.set_edge_behavior(EdgeBehavior::Wrap);
+        assert_eq!(
+            neighbor_cells(&world, (0, 0)),
+            [alive, alive, alive, alive, dead, dead, dead, alive]
+        );
+    }
+
+    #[test]
+    fn gliders_go_around_a_cylinder_but_not_over_the_top() {
+        // A glider heading up and to the right, with the top and bottom edges left dead.
+        let glider = world("OOO\n..O\n.O.");
+        let mut cylinder = World::new(8, 12);
+        cylinder.overlay(&glider, (5, 8));
+        cylinder.set_edge_behavior(EdgeBehavior::WrapHorizontal);
+
+        cylinder = cylinder.tick_n(12, &Rule::conway());
+        assert!(cylinder.same_pattern(&glider));
+        assert_eq!(cylinder.bounding_box(), Some(((0, 5), (2, 7))));
+
+        // Once it reaches the top, all that's left of it is a block.
+        cylinder = cylinder.tick_n(48, &Rule::conway());
+        assert!(cylinder.same_pattern(&world("OO\nOO")));
+        assert_eq!(cylinder.bounding_box(), Some(((6, 0), (7, 1))));
+    }
+
+    #[test]
+    fn set_and_toggle_only_touch_cells_inside_the_world() {
+        let mut grid = World::new(3, 2);
+
+        assert!(grid.set((2, 1), Cell::Alive));
+        assert!(grid.toggle((0, 0)));
+        assert_eq!(grid, world("O..\n..O"));
+
+        assert!(grid.toggle((0, 0)));
+        assert!(grid.set((2, 1), Cell::Marker));
+        assert_eq!(grid[(2, 1)], Cell::Marker);
+        assert!(grid.is_empty());
+
+        assert!(!grid.set((3, 0), Cell::Alive));
+        assert!(!grid.set((0, 2), Cell::Alive));
+        assert!(!grid.toggle((usize::MAX, usize::MAX)));
+        assert_eq!(grid.population(), 0);
+    }
+
+    #[test]
+    fn masked_neighbors_only_count_the_given_directions() {
+        let orthogonal = [
+            Position::Top,
+            Position::Left,
+            Position::Right,
+            Position::Bottom,
+        ];
+        let plus = world(".O.\nOOO\n.O.");
+        let cross = world("O.O\n.O.\nO.O");
+
+        assert_eq!(plus.live_neighbors_masked((1, 1), &orthogonal), 4);
+        assert_eq!(cross.live_neighbors_masked((1, 1), &orthogonal), 0);
+        assert_eq!(cross.live_neighbors_masked((1, 1), &Position::all()), 4);
+        assert_eq!(plus.live_neighbors_masked((0, 0), &orthogonal), 2);
+        assert_eq!(
+            plus.live_neighbors_masked((1, 1), &[Position::Top, Position::Top]),
+            1
+        );
+    }
+
+    #[test]
+    fn bool_grids_round_trip() {
+        let rows = vec![vec![true, false, false], vec![false, true, true]];
+        let grid = World::from_bool_grid(&rows).unwrap();
+
+        assert_eq!(grid, world("O..\n.OO"));
+        assert_eq!(grid.to_bool_grid(), rows);
+        assert_eq!(World::from_bool_grid(&[]).unwrap(), World::new(0, 0));
+    }
+
+    #[test]
+    fn ragged_bool_grids_are_errors() {
+        let rows = vec![vec![true, false], vec![true], vec![false, false]];
+
+        assert_eq!(
+            World::from_bool_grid(&rows),
+            Err(WorldError::RaggedRows {
+                row: 1,
+                expected: 2,
+                found: 1
+            })
+        );
+    }
+
+    #[test]
+    fn subgrids_copy_interior_regions() {
+        let grid = world("O...\n.OO.\n.O.O");
+
+        assert_eq!(
+            grid.subgrid((1, 1), 2, 2, Overflow::Error),
+            Ok(world("OO\nO."))
+        );
+        assert_eq!(
+            grid.subgrid((0, 0), 4, 3, Overflow::Error),
+            Ok(grid.clone())
+        );
+    }
+
+    #[test]
+    fn subgrids_past_the_edge_error_or_clip() {
+        let grid = world("O...\n.OO.\n.O.O");
+
+        assert_eq!(
+            grid.subgrid((2, 1), 3, 3, Overflow::Error),
+            Err(WorldError::OutOfBounds {
+                top_left: (2, 1),
+                width: 3,
+                height: 3
+            })
+        );
+        assert_eq!(
+            grid.subgrid((2, 1), 3, 3, Overflow::Drop),
+            Ok(world("O.\n.O"))
+        );
+        assert_eq!(
+            grid.subgrid((4, 0), 2, 2, Overflow::Drop),
+            Ok(World::new(0, 0))
+        );
+    }
+
+    #[test]
+    fn zero_area_subgrids_are_empty() {
+        let grid = world("O...\n.OO.\n.O.O");
+
+        assert_eq!(
+            grid.subgrid((1, 1), 0, 2, Overflow::Error),
+            Ok(World::new(0, 0))
+        );
+        assert_eq!(
+            grid.subgrid((10, 10), 3, 0, Overflow::Error),
+            Ok(World::new(0, 0))
+        );
+    }
+
+    #[test]
+    fn every_transform_of_a_glider_has_the_same_canonical_form() {
+        let glider = world(".O.\n..O\nOOO");
+        let canonical = glider.canonical();
+        let mut transforms = Vec::new();
+
+        for start in [glider.clone(), glider.mirrored()] {
+            let mut current = start;
+
+            for _ in 0..4 {
+                current = current.rotated();
+                transforms.push(current.clone());
+            }
+        }
+
+        // The glider has no symmetry, so all eight transforms are different from each other.
+        for (index, transform) in transforms.iter().enumerate() {
+            assert!(transforms[..index]
+                .iter()
+                .all(|earlier| earlier != transform));
+            assert_eq!(transform.canonical(), canonical);
+        }
+    }
+
+    #[test]
+    fn canonical_forms_ignore_position_and_settings() {
+        let mut moved = World::new(10, 10);
+        moved.overlay(&world(".O.\n..O\nOOO"), (6, 2));
+        moved.set_edge_behavior(EdgeBehavior::Wrap);
+        moved.set_rule(Rule::new(&[3, 6], &[2, 3]));
+
+        assert_eq!(moved.canonical(), world(".O.\n..O\nOOO").canonical());
+        assert_ne!(moved.canonical(), world("OO\nOO").canonical());
+    }
+
+    #[test]
+    fn neighbor_masks_follow_the_order_of_positions() {
+        // Only the top left, right and bottom neighbors of the middle cell are alive.
+        let grid = world("O..\n.OO\n.O.");
+
+        assert_eq!(grid.neighbor_mask((1, 1)), 0b0101_0001);
+        assert_eq!(grid.live_neighbors((1, 1)), 3);
+
+        for (bit, direction) in Position::all().into_iter().enumerate() {
+            let (dx, dy) = direction.offset();
+            let mut single = World::new(3, 3);
+            single[((1 + dx) as usize, (1 + dy) as usize)] = Cell::Alive;
+
+            assert_eq!(single.neighbor_mask((1, 1)), 1 << bit, "{:?}", direction);
+        }
+    }
+
+    #[test]
+    fn two_separate_blocks_are_two_clusters() {
+        let blocks = world("OO...\nOO...\n.....\n...OO\n...OO");
+        assert_eq!(blocks.cluster_count(), 2);
+    }
+
+    #[test]
+    fn clusters_join_diagonally_and_across_wrapped_edges() {
+        assert_eq!(world("O..\n.O.\n..O").cluster_count(), 1);
+        assert_eq!(world("O.O\n...\nO.O").cluster_count(), 4);
+        assert_eq!(World::new(3, 3).cluster_count(), 0);
+
+        let mut wrapped = world("O...O\n.....\n.....");
+        assert_eq!(wrapped.cluster_count(), 2);
+        wrapped.set_edge_behavior(EdgeBehavior::WrapHorizontal);
+        assert_eq!(wrapped.cluster_count(), 1);
+    }
+
+    #[test]
+    fn convolving_with_a_ring_counts_live_neighbors() {
+        let ring = [[1, 1, 1], [1, 0, 1], [1, 1, 1]];
+
+        for (seed, edges) in EDGE_BEHAVIORS.into_iter().enumerate() {
+            for (width, height) in [(1, 1), (2, 2), (3, 5), (9, 7)] {
+                let mut grid = World::random(width, height, 0.5, &mut Rng::new(seed as u64));
+                grid.set_edge_behavior(edges);
+
+                let expected: Vec<i32> = grid
+                    .iter()
+                    .map(|cell| grid.live_neighbors(cell.position) as i32)
+                    .collect();
+
+                assert_eq!(
+                    grid.convolve3x3(ring),
+                    expected,
+                    "{}x{} with {:?} edges",
+                    width,
+                    height,
+                    edges
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn convolving_weights_each_part_of_the_neighborhood() {
+        let grid = world("O..\n.O.\n..O");
+
+        // Only the cell itself and the cell below and to the right of it are weighted.
+        let kernel = [[0, 0, 0], [0, 10, 0], [0, 0, 1]];
+        assert_eq!(grid.convolve3x3(kernel), [11, 0, 0, 0, 11, 0, 0, 0, 10]);
+    }
+
+    #[test]
+    fn cells_along_a_diagonal() {
+        let grid = world("O...\n.O..\n..O.\n...O");
+        let line: Vec<_> = grid.cells_along((0, 0), (3, 3)).collect();
+
+        assert_eq!(line, [0, 1, 2, 3].map(|i| (i, i, Cell::Alive)));
+
+        let backwards: Vec<_> = grid
+            .cells_along((3, 0), (0, 3))
+            .map(|(x, y, _)| (x, y))
+            .collect();
+        assert_eq!(backwards, [(3, 0), (2, 1), (1, 2), (0, 3)]);
+    }
+
+    #[test]
+    fn cells_along_a_line_skip_anything_off_the_grid() {
+        let grid = World::new(3, 3);
+        let line: Vec<_> = grid
+            .cells_along((1, 1), (5, 5))
+            .map(|(x, y, _)| (x, y))
+            .collect();
+
+        assert_eq!(line, [(1, 1), (2, 2)]);
+        assert_eq!(grid.cells_along((1, 0), (1, 0)).count(), 1);
+    }
+
+    #[test]
+    fn fill_border_builds_a_wall() {
+        let mut grid = World::new(5, 5);
+        grid.fill_border(Cell::Alive, 1);
+        assert_eq!(grid, world("OOOOO\nO...O\nO...O\nO...O\nOOOOO"));
+
+        grid.fill_border(Cell::Dead, 1);
+        assert!(grid.is_empty());
+
+        grid.fill_border(Cell::Alive, 0);
+        assert!(grid.is_empty());
+
+        // A border at least half as thick as the grid fills all of it.
+        grid.fill_border(Cell::Alive, 3);
+        assert!(grid.is_full());
+    }
+
+    #[test]
+    fn ticking_into_a_dirty_buffer_overwrites_all_of_it() {
+        let glider = Format::Rle.parse("x = 8, y = 8\nbo$2bo$3o!").unwrap();
+
+        // A full world of the wrong size, with different settings to the glider's.
+        let mut out = World::new(5, 3).map(|_, _| Cell::Alive);
+        out.set_edge_behavior(EdgeBehavior::Wrap);
+        out.set_rule("B36/S23".parse().unwrap());
+        out.metadata_mut().name = Some("Junk".to_string());
+
+        glider.tick_into(&mut out);
+        assert_eq!(out, glider.clone().tick());
+
+        // A dirty buffer of the right size is reused rather than replaced, and is overwritten all the same.
+        let mut next = World::new(8, 8).map(|_, _| Cell::Alive);
+        out.tick_into(&mut next);
+        assert_eq!(next, glider.tick_n(2, &Rule::conway()));
+    }
+
+    #[test]
+    fn stepping_says_whether_anything_changed() {
+        let mut block = world("OO\nOO");
+        assert!(!block.step());
+        assert_eq!(block, world("OO\nOO"));
+
+        let mut blinker = world("...\nOOO\n...");
+        assert!(blinker.step());
+        assert_eq!(blinker, world(".O.\n.O.\n.O."));
+        assert!(blinker.step());
+        assert_eq!(blinker, world("...\nOOO\n..."));
+    }
+
+    #[test]
+    fn generations_match_ticking_that_many_times() {
+        let glider = Format::Rle.parse("x = 8, y = 8\nbo$2bo$3o!").unwrap();
+
+        // Generation 1 comes first, so the nth world is generation n.
+        for n in 1..=8 {
+            let ticked = glider.clone().tick_n(n, &Rule::conway());
+            assert_eq!(
+                glider.clone().generations().take(n).last(),
+                Some(ticked.clone())
+            );
+
+            let mut generations = glider.clone().generations();
+            for _ in 1..n {
+                generations.advance();
+            }
+            assert_eq!(generations.advance(), &ticked);
+        }
+    }
+
+    #[test]
+    fn observed_ticks_report_each_birth_and_death_once() {
+        let mut events = Vec::new();
+        let blinker = world("...\nOOO\n...");
+        let next = blinker.tick_observe(|event| events.push(event));
+
+        assert_eq!(next, world(".O.\n.O.\n.O."));
+        assert_eq!(
+            events,
+            [
+                CellEvent::Born((1, 0)),
+                CellEvent::Died((0, 1)),
+                CellEvent::Died((2, 1)),
+                CellEvent::Born((1, 2)),
+            ]
+        );
+
+        let block = world("OO\nOO");
+        block.tick_observe(|event| panic!("a block shouldn't change, but got {:?}", event));
+    }
+}